@@ -0,0 +1,147 @@
+use super::{Agenda, AgendaItem, AgendaItemKind, CalendarEvent, EventDateTime, Task};
+use crate::config::{AppConfig, TaskProviderKind};
+use chrono::{DateTime, Duration, Local, NaiveDate, TimeZone};
+
+/// Slot length for tasks that only have a due time
+const TASK_SLOT_MINUTES: i64 = 30;
+
+/// Local midnight at the start of `date`
+fn local_midnight(date: NaiveDate) -> Option<DateTime<Local>> {
+    date.and_hms_opt(0, 0, 0)
+        .and_then(|d| Local.from_local_datetime(&d).earliest())
+}
+
+/// A Google date or date-time in local time, and whether it's a whole day
+fn event_time(time: &EventDateTime) -> Option<(DateTime<Local>, bool)> {
+    if let Some(date_time) = &time.date_time {
+        let parsed = DateTime::parse_from_rfc3339(date_time).ok()?;
+        return Some((parsed.with_timezone(&Local), false));
+    }
+
+    let date = NaiveDate::parse_from_str(time.date.as_deref()?, "%Y-%m-%d").ok()?;
+    Some((local_midnight(date)?, true))
+}
+
+fn from_event(event: CalendarEvent) -> Option<AgendaItem> {
+    let (start, all_day) = event_time(&event.start)?;
+    // All-day events end at midnight after their last day, as Google sends them
+    let (end, _) = event_time(&event.end)?;
+
+    Some(AgendaItem {
+        kind: AgendaItemKind::Event,
+        id: event.id.clone(),
+        title: event.summary.clone(),
+        start: start.to_rfc3339(),
+        end: end.to_rfc3339(),
+        all_day,
+        time_zone: event.start.time_zone.clone(),
+        color: Some(event.calendar_color.clone()),
+        source: event.calendar_name.clone(),
+        event: Some(event),
+        task: None,
+    })
+}
+
+/// All-day tasks cover their due day; timed tasks take a short slot ending
+/// at their due time
+fn from_task(task: Task) -> Option<AgendaItem> {
+    let due = task.due_date.as_deref()?;
+    let (start, end) = if task.is_all_day {
+        let day = NaiveDate::parse_from_str(due, "%Y-%m-%d").ok()?;
+        let start = local_midnight(day)?;
+        (start, start + Duration::days(1))
+    } else {
+        let due = DateTime::parse_from_rfc3339(due)
+            .ok()?
+            .with_timezone(&Local);
+        (due - Duration::minutes(TASK_SLOT_MINUTES), due)
+    };
+
+    Some(AgendaItem {
+        kind: AgendaItemKind::Task,
+        id: task.id.clone(),
+        title: task.title.clone(),
+        start: start.to_rfc3339(),
+        end: end.to_rfc3339(),
+        all_day: task.is_all_day,
+        time_zone: None,
+        color: None,
+        source: task
+            .list_name
+            .clone()
+            .unwrap_or_else(|| "Inbox".to_string()),
+        event: None,
+        task: Some(task),
+    })
+}
+
+/// Open tasks from the configured provider, or none when it's TickTick and
+/// TickTick isn't connected
+async fn fetch_tasks(config: &AppConfig) -> Result<Vec<Task>, String> {
+    if config.tasks.provider == TaskProviderKind::TickTick
+        && config.ticktick.access_token.is_empty()
+    {
+        return Ok(Vec::new());
+    }
+
+    super::tasks::fetch_tasks(config)
+        .await
+        .map(|data| data.tasks)
+}
+
+/// Calendar events and dated tasks from today through the next `days` days,
+/// in local time and sorted by start. One source failing only adds an error
+pub async fn get_agenda(config: &AppConfig, days: u32) -> Result<Agenda, String> {
+    let today = Local::now().date_naive();
+    let range_start = local_midnight(today).ok_or("Invalid local date")?;
+    let range_end = range_start + Duration::days(days.max(1) as i64);
+
+    let calendar = async {
+        if super::calendar::is_calendar_configured() {
+            super::calendar::fetch_calendar_events().await
+        } else {
+            Ok(Vec::new())
+        }
+    };
+    let (events, tasks) = tokio::join!(calendar, fetch_tasks(config));
+
+    let mut errors = Vec::new();
+    let mut items = Vec::new();
+
+    match events {
+        Ok(events) => items.extend(events.into_iter().filter_map(from_event)),
+        Err(e) => errors.push(format!("Calendar: {}", e)),
+    }
+    match tasks {
+        Ok(tasks) => items.extend(tasks.into_iter().filter_map(from_task)),
+        Err(e) => errors.push(format!("Tasks: {}", e)),
+    }
+
+    if items.is_empty() && errors.len() == 2 {
+        return Err(format!("Failed to load agenda: {}", errors.join(", ")));
+    }
+
+    // Keep anything overlapping the range, including events already under way
+    items.retain(|item| {
+        let start = DateTime::parse_from_rfc3339(&item.start);
+        let end = DateTime::parse_from_rfc3339(&item.end);
+        matches!((start, end), (Ok(s), Ok(e)) if s < range_end && e > range_start)
+    });
+
+    // Whole-day items first within a day, then by time
+    items.sort_by(|a, b| {
+        let day = |item: &AgendaItem| item.start.get(..10).map(str::to_string);
+        day(a)
+            .cmp(&day(b))
+            .then_with(|| b.all_day.cmp(&a.all_day))
+            .then_with(|| a.start.cmp(&b.start))
+    });
+
+    Ok(Agenda {
+        items,
+        start: range_start.to_rfc3339(),
+        end: range_end.to_rfc3339(),
+        errors,
+        last_updated: Local::now().to_rfc3339(),
+    })
+}
@@ -0,0 +1,153 @@
+use super::{SessionState, StockAlert, StockData};
+use crate::config::{get_config_dir, AlertCondition, AlertRule};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::PathBuf;
+
+/// Alerts already sent today, so each rule fires at most once a day
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct FiredStore {
+    date: String,
+    fired: BTreeSet<String>,
+}
+
+fn store_path() -> Result<PathBuf, String> {
+    get_config_dir()
+        .map(|p| p.join("stock_alerts.json"))
+        .map_err(|e| e.to_string())
+}
+
+fn load_store(today: &str) -> FiredStore {
+    let store = store_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str::<FiredStore>(&content).ok());
+
+    match store {
+        Some(store) if store.date == today => store,
+        _ => FiredStore {
+            date: today.to_string(),
+            fired: BTreeSet::new(),
+        },
+    }
+}
+
+fn save_store(store: &FiredStore) -> Result<(), String> {
+    let path = store_path()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create config dir: {}", e))?;
+    }
+
+    let content = serde_json::to_string(store)
+        .map_err(|e| format!("Failed to serialize fired alerts: {}", e))?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write fired alerts: {}", e))
+}
+
+/// Identifies a rule in the fired store; changing the threshold makes it a new rule
+fn rule_key(rule: &AlertRule) -> String {
+    format!("{}:{:?}", rule.ticker, rule.condition)
+}
+
+/// Description of a triggered rule, or None if it doesn't apply
+fn check_rule(condition: &AlertCondition, stock: &StockData) -> Option<String> {
+    let price = format!(
+        "{}{:.*}",
+        stock.currency,
+        stock.price_hint.max(0) as usize,
+        stock.price
+    );
+
+    match *condition {
+        AlertCondition::Above { price: level } if stock.price >= level => Some(format!(
+            "{} is at {}, above {}{}",
+            stock.ticker, price, stock.currency, level
+        )),
+        AlertCondition::Below { price: level } if stock.price <= level => Some(format!(
+            "{} is at {}, below {}{}",
+            stock.ticker, price, stock.currency, level
+        )),
+        // Outside the regular and post-market sessions the change still
+        // describes the previous day, which was already alerted on. Providers
+        // without session info can't tell the two apart, so they never fire
+        AlertCondition::PercentChange { percent }
+            if matches!(
+                stock.market.state,
+                SessionState::Regular | SessionState::Post
+            ) && stock.change_percent.abs() >= percent =>
+        {
+            Some(format!(
+                "{} is {} {:.2}% today, at {}",
+                stock.ticker,
+                if stock.change_percent >= 0.0 {
+                    "up"
+                } else {
+                    "down"
+                },
+                stock.change_percent.abs(),
+                price
+            ))
+        }
+        AlertCondition::FiftyTwoWeekHigh
+            if stock
+                .fifty_two_week_high
+                .is_some_and(|high| stock.price >= high) =>
+        {
+            Some(format!("{} hit a 52-week high of {}", stock.ticker, price))
+        }
+        AlertCondition::FiftyTwoWeekLow
+            if stock
+                .fifty_two_week_low
+                .is_some_and(|low| stock.price <= low) =>
+        {
+            Some(format!("{} hit a 52-week low of {}", stock.ticker, price))
+        }
+        _ => None,
+    }
+}
+
+/// Evaluate alert rules against fresh quotes, returning only alerts that
+/// haven't already fired today
+pub fn evaluate_alerts(stocks: &[StockData], rules: &[AlertRule]) -> Vec<StockAlert> {
+    if rules.is_empty() {
+        return Vec::new();
+    }
+
+    let now = Local::now();
+    let mut store = load_store(&now.format("%Y-%m-%d").to_string());
+
+    let alerts: Vec<StockAlert> = rules
+        .iter()
+        .filter_map(|rule| {
+            let stock = stocks
+                .iter()
+                .find(|s| s.ticker.eq_ignore_ascii_case(&rule.ticker))?;
+            let key = rule_key(rule);
+            if store.fired.contains(&key) {
+                return None;
+            }
+
+            let message = check_rule(&rule.condition, stock)?;
+            store.fired.insert(key);
+
+            Some(StockAlert {
+                ticker: stock.ticker.clone(),
+                name: stock.name.clone(),
+                condition: rule.condition.clone(),
+                price: stock.price,
+                currency: stock.currency.clone(),
+                message,
+                triggered_at: now.to_rfc3339(),
+            })
+        })
+        .collect();
+
+    if !alerts.is_empty() {
+        if let Err(e) = save_store(&store) {
+            log::warn!("Stocks: {}", e);
+        }
+    }
+
+    alerts
+}
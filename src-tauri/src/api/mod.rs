@@ -1,8 +1,14 @@
 pub mod weather;
 pub mod stocks;
+pub mod portfolio;
+pub mod alerts;
 pub mod ticktick;
 pub mod calendar;
+pub mod agenda;
+pub mod tasks;
 
+use crate::config::{AlertCondition, AssetKind, TaskGrouping, TaskProviderKind};
+use crate::sensors::SensorReading;
 use serde::{Deserialize, Serialize};
 
 // Shared types for API responses
@@ -11,7 +17,7 @@ use serde::{Deserialize, Serialize};
 #[serde(rename_all = "camelCase")]
 pub struct WeatherData {
     pub location: WeatherLocation,
-    pub condition: String,
+    pub condition: WeatherCondition,
     pub temperature: i32,
     pub feels_like: i32,
     pub humidity: i32,
@@ -20,6 +26,10 @@ pub struct WeatherData {
     pub sunrise: String,
     pub sunset: String,
     pub hourly_forecast: Vec<HourlyWeather>,
+    pub air_quality: Option<AirQuality>,
+    /// Local sensor readings, filled in on every fetch (not cached with the forecast)
+    #[serde(default)]
+    pub measured: Vec<SensorReading>,
     pub last_updated: String,
 }
 
@@ -34,7 +44,111 @@ pub struct WeatherLocation {
 pub struct HourlyWeather {
     pub hour: i32,
     pub temperature: i32,
-    pub condition: String,
+    pub condition: WeatherCondition,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WeatherCondition {
+    pub category: ConditionCategory,
+    /// Only set for precipitation and thunderstorms
+    pub intensity: Option<Intensity>,
+    pub precipitation: Option<PrecipitationType>,
+    pub is_day: bool,
+    /// Human readable summary, e.g. "Heavy freezing rain"
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConditionCategory {
+    Clear,
+    PartlyCloudy,
+    Cloudy,
+    Fog,
+    Drizzle,
+    Rain,
+    Snow,
+    Thunderstorm,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Intensity {
+    Light,
+    Moderate,
+    Heavy,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum PrecipitationType {
+    Rain,
+    Freezing,
+    Snow,
+    Hail,
+}
+
+/// Observed weather for a single day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyWeatherSummary {
+    /// Local date in "YYYY-MM-DD" format
+    pub date: String,
+    pub high: f64,
+    pub low: f64,
+    /// Total precipitation in mm
+    pub precipitation: f64,
+    /// Dominant condition for the day
+    pub condition: WeatherCondition,
+    pub hourly: Vec<HourlyWeather>,
+}
+
+/// Aggregates over a range of days, for week-over-week comparisons
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeriodSummary {
+    pub start_date: String,
+    pub end_date: String,
+    pub avg_high: Option<f64>,
+    pub avg_low: Option<f64>,
+    pub total_precipitation: f64,
+    /// Days with at least 1mm of precipitation
+    pub wet_days: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WeatherHistory {
+    /// Oldest first, ending with today (which is still in progress)
+    pub days: Vec<DailyWeatherSummary>,
+    pub this_week: PeriodSummary,
+    pub last_week: PeriodSummary,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AirQuality {
+    /// US AQI (0-500), None when the model has no data for the location
+    pub aqi: Option<i32>,
+    /// "good", "moderate", "unhealthy-sensitive", "unhealthy", "very-unhealthy" or "hazardous"
+    pub aqi_category: String,
+    pub pm25: Option<f64>,
+    pub pm10: Option<f64>,
+    pub ozone: Option<f64>,
+    pub pollen: PollenCounts,
+    pub last_updated: String,
+}
+
+/// Pollen counts in grains/m³ (only available for Europe during pollen season)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PollenCounts {
+    pub alder: Option<f64>,
+    pub birch: Option<f64>,
+    pub grass: Option<f64>,
+    pub mugwort: Option<f64>,
+    pub olive: Option<f64>,
+    pub ragweed: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -42,12 +156,258 @@ pub struct HourlyWeather {
 pub struct StockData {
     pub ticker: String,
     pub name: String,
+    pub asset_kind: AssetKind,
     pub price: f64,
     pub change: f64,
     pub change_percent: f64,
+    /// Currency symbol for display (e.g. "C$"), empty for index levels and FX rates
     pub currency: String,
+    /// ISO currency code as reported by the exchange (e.g. "CAD")
+    pub currency_code: String,
+    /// Set when a base currency is configured and the FX rate could be fetched
+    pub base_currency: Option<String>,
+    pub fx_rate: Option<f64>,
+    pub price_in_base: Option<f64>,
+    pub change_in_base: Option<f64>,
     pub sparkline_data: Vec<f64>,
     pub price_hint: i32,
+    pub fifty_two_week_high: Option<f64>,
+    pub fifty_two_week_low: Option<f64>,
+    /// Dividends and splits over the last year, empty for providers without them
+    #[serde(default)]
+    pub corporate_actions: CorporateActions,
+    pub market: MarketSession,
+    /// Latest pre/post-market trade, only set outside regular hours
+    pub extended_hours: Option<ExtendedHoursQuote>,
+    pub last_updated: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CorporateActions {
+    /// Oldest first
+    pub dividends: Vec<DividendEvent>,
+    /// Oldest first
+    pub splits: Vec<SplitEvent>,
+    /// Sum of the last 12 months of dividends, per share
+    pub trailing_annual_dividend: Option<f64>,
+    /// Trailing annual dividend as a percentage of the current price
+    pub dividend_yield: Option<f64>,
+    /// Next ex-dividend date projected from the payment cadence. Announced
+    /// dates aren't available from the chart API, so treat this as a guess
+    pub estimated_next_dividend: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DividendEvent {
+    /// Ex-dividend date (YYYY-MM-DD)
+    pub date: String,
+    /// Amount per share, in the stock's currency
+    pub amount: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SplitEvent {
+    /// Effective date (YYYY-MM-DD)
+    pub date: String,
+    /// New shares per `denominator` old shares, e.g. 4 for a 4:1 split
+    pub numerator: f64,
+    pub denominator: f64,
+}
+
+/// Payload of the `stock-alert` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StockAlert {
+    pub ticker: String,
+    pub name: String,
+    pub condition: AlertCondition,
+    pub price: f64,
+    pub currency: String,
+    pub message: String,
+    pub triggered_at: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum SessionState {
+    Pre,
+    Regular,
+    Post,
+    Closed,
+    /// The provider doesn't report trading hours
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketSession {
+    pub state: SessionState,
+    /// IANA timezone of the exchange, e.g. "America/Toronto"
+    pub exchange_timezone: Option<String>,
+    /// Time of the last regular-hours trade. `change` is relative to the
+    /// session before this one, so outside regular hours it's not "today"
+    pub regular_market_time: Option<String>,
+    /// Estimated start of the next regular session (weekends are skipped, holidays are not)
+    pub next_open: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtendedHoursQuote {
+    pub session: SessionState,
+    pub price: f64,
+    /// Change from the regular-hours price
+    pub change: f64,
+    pub change_percent: f64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum ChartRange {
+    #[serde(rename = "1d")]
+    OneDay,
+    #[serde(rename = "5d")]
+    FiveDays,
+    #[serde(rename = "1mo")]
+    OneMonth,
+    #[serde(rename = "6mo")]
+    SixMonths,
+    #[serde(rename = "1y")]
+    OneYear,
+    #[serde(rename = "5y")]
+    FiveYears,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OhlcPoint {
+    /// Unix timestamp in seconds
+    pub timestamp: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StockChart {
+    pub ticker: String,
+    pub range: ChartRange,
+    /// Bar size, e.g. "5m" or "1d"
+    pub interval: String,
+    pub currency_code: String,
+    pub price_hint: i32,
+    /// Close before the first point, for drawing a baseline
+    pub previous_close: Option<f64>,
+    pub points: Vec<OhlcPoint>,
+    pub last_updated: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StocksData {
+    /// Quotes for the tickers that could be fetched, in config order
+    pub stocks: Vec<StockData>,
+    /// One entry per configured ticker, including failures
+    pub statuses: Vec<TickerStatus>,
+    /// Suggested delay before the next refresh, longer while all markets are closed
+    pub refresh_interval_minutes: u32,
+    pub last_updated: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TickerStatus {
+    pub ticker: String,
+    pub status: FetchStatus,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum FetchStatus {
+    Ok,
+    Timeout,
+    /// The provider doesn't know the symbol, usually a typo
+    NotFound,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TickerValidation {
+    pub ticker: String,
+    pub status: FetchStatus,
+    pub name: Option<String>,
+    pub asset_kind: Option<AssetKind>,
+    pub currency_code: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SymbolMatch {
+    pub symbol: String,
+    pub name: String,
+    /// Exchange display name, e.g. "Toronto"
+    pub exchange: String,
+    pub asset_kind: AssetKind,
+    /// Yahoo's type label, e.g. "ETF" or "Equity"
+    pub type_display: String,
+    /// Trading currency, when it could be looked up
+    pub currency_code: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortfolioHolding {
+    pub ticker: String,
+    pub name: String,
+    pub currency: String,
+    pub currency_code: String,
+    pub shares: f64,
+    pub price: f64,
+    /// Weighted average price paid per share
+    pub average_cost: f64,
+    /// Total amount paid across all lots
+    pub cost_basis: f64,
+    pub market_value: f64,
+    /// Change in market value since the previous close
+    pub day_change: f64,
+    pub unrealized_gain: f64,
+    pub unrealized_gain_percent: f64,
+    /// Rate used to convert into the portfolio's base currency (1.0 without one)
+    pub fx_rate: f64,
+    pub market_value_base: f64,
+    pub day_change_base: f64,
+    pub unrealized_gain_base: f64,
+    /// Projected dividends over the next year at the trailing rate
+    pub annual_income: Option<f64>,
+    pub annual_income_base: Option<f64>,
+    /// Annual income as a percentage of cost basis
+    pub yield_on_cost: Option<f64>,
+    /// Share of the portfolio's total market value (0-100)
+    pub weight: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Portfolio {
+    pub holdings: Vec<PortfolioHolding>,
+    /// Currency the totals are reported in. Without a base currency, totals
+    /// are plain sums across whatever currencies the holdings trade in
+    pub base_currency: Option<String>,
+    pub total_market_value: f64,
+    pub total_cost_basis: f64,
+    pub total_day_change: f64,
+    pub total_unrealized_gain: f64,
+    pub total_unrealized_gain_percent: f64,
+    /// Projected dividends over the next year across all holdings
+    pub total_annual_income: f64,
     pub last_updated: String,
 }
 
@@ -56,11 +416,93 @@ pub struct StockData {
 pub struct TickTickData {
     pub tasks: Vec<TickTickTask>,
     pub projects: Vec<TickTickProject>,
+    /// Projects whose tasks are missing from `tasks` because fetching them failed
+    #[serde(default)]
+    pub failed_projects: Vec<ProjectFailure>,
+    /// What changed since the previous sync, empty when served from the local store
+    #[serde(default)]
+    pub changes: TaskChanges,
+    /// Changes made from the dashboard that haven't reached TickTick yet
+    #[serde(default)]
+    pub pending_actions: Vec<PendingTaskAction>,
+    /// TickTick couldn't be reached, so this is the last synced state plus
+    /// any pending changes
+    #[serde(default)]
+    pub offline: bool,
+    pub last_updated: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskQueryResult {
+    pub query: String,
+    pub group_by: TaskGrouping,
+    /// Number of matching tasks across all groups
+    pub total: usize,
+    pub groups: Vec<TaskGroup>,
     pub last_updated: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
+pub struct TaskGroup {
+    pub key: String,
+    pub label: String,
+    pub tasks: Vec<TickTickTask>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskChanges {
+    pub added: Vec<TickTickTask>,
+    pub updated: Vec<TickTickTask>,
+    /// Ids of tasks that were completed, deleted or moved out of an open project
+    pub removed: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectFailure {
+    pub project_id: String,
+    pub project_name: String,
+    pub error: String,
+}
+
+/// A change made while TickTick was unreachable, replayed in order once it's back
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingTaskAction {
+    pub id: String,
+    pub action: TaskAction,
+    pub queued_at: String,
+    /// The task's `modified_time` when the change was made. TickTick edits
+    /// after the change was queued win over it
+    pub base_modified_time: Option<String>,
+    pub attempts: u32,
+    /// Why TickTick rejected the change or it lost a conflict. Failed actions
+    /// are skipped until discarded
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case", rename_all_fields = "camelCase")]
+pub enum TaskAction {
+    Complete {
+        project_id: String,
+        task_id: String,
+    },
+    /// `local_id` is the placeholder id the task has until it's created
+    Create {
+        task: NewTickTickTask,
+        local_id: String,
+    },
+    Update {
+        update: TickTickTaskUpdate,
+    },
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
 pub struct TickTickTask {
     pub id: String,
     pub title: String,
@@ -73,6 +515,109 @@ pub struct TickTickTask {
     pub tags: Vec<String>,
     pub created_time: String,
     pub modified_time: String,
+    /// Description, from `content` (or `desc` on checklist tasks)
+    #[serde(default)]
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub checklist: Vec<ChecklistItem>,
+    /// None when the task has no checklist
+    #[serde(default)]
+    pub checklist_progress: Option<ChecklistProgress>,
+    /// RRULE for repeating tasks, e.g. "RRULE:FREQ=WEEKLY;INTERVAL=1"
+    #[serde(default)]
+    pub recurrence: Option<String>,
+    #[serde(default)]
+    pub is_all_day: bool,
+    /// IANA timezone the task's dates were set in
+    #[serde(default)]
+    pub time_zone: Option<String>,
+    /// For all-day tasks, the due day in the task's timezone ("YYYY-MM-DD").
+    /// `due_date` is midnight in that timezone as UTC, which can land on the
+    /// previous day elsewhere
+    #[serde(default)]
+    pub due_day: Option<String>,
+    /// Reminders in minutes relative to the due date, negative before it
+    #[serde(default)]
+    pub reminder_offsets: Vec<i64>,
+    #[serde(default)]
+    pub completed_time: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CompletedRange {
+    Today,
+    /// Since Monday
+    Week,
+    /// The last 30 days
+    Month,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompletedTasks {
+    pub range: CompletedRange,
+    /// Most recently completed first
+    pub tasks: Vec<TickTickTask>,
+    pub stats: CompletionStats,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompletionStats {
+    pub today: usize,
+    pub this_week: usize,
+    /// Consecutive days with at least one completion, ending today (or
+    /// yesterday, so the streak isn't broken before today's first task)
+    pub streak_days: u32,
+    /// Completions per day across the requested range, oldest first
+    pub daily: Vec<DailyCount>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyCount {
+    pub date: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChecklistItem {
+    pub id: String,
+    pub title: String,
+    pub is_completed: bool,
+    pub sort_order: i64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChecklistProgress {
+    pub completed: usize,
+    pub total: usize,
+}
+
+/// A task to create from the dashboard. `due_date` is RFC 3339, or a plain
+/// date for an all-day task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewTickTickTask {
+    pub title: String,
+    pub project_id: String,
+    pub due_date: Option<String>,
+    #[serde(default)]
+    pub priority: i32,
+}
+
+/// Changes to an existing task. Fields left out keep their current value
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TickTickTaskUpdate {
+    pub id: String,
+    pub project_id: String,
+    pub title: Option<String>,
+    pub due_date: Option<String>,
+    pub priority: Option<i32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -84,6 +629,45 @@ pub struct TickTickProject {
     pub sort_order: i64,
 }
 
+/// Tasks from whichever provider is configured
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TasksData {
+    pub provider: TaskProviderKind,
+    pub tasks: Vec<Task>,
+    pub lists: Vec<TaskList>,
+    pub last_updated: String,
+}
+
+/// An open task, the same shape for every provider
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Task {
+    pub id: String,
+    pub list_id: String,
+    pub list_name: Option<String>,
+    pub title: String,
+    pub notes: Option<String>,
+    /// RFC 3339, or YYYY-MM-DD when `is_all_day`
+    pub due_date: Option<String>,
+    pub is_all_day: bool,
+    /// 0=none, 1=low, 3=medium, 5=high, as in TickTick
+    pub priority: i32,
+    pub tags: Vec<String>,
+    pub is_completed: bool,
+    /// RFC 3339, when the provider tracks it
+    pub modified_time: Option<String>,
+}
+
+/// A project, calendar collection or file section holding tasks
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskList {
+    pub id: String,
+    pub name: String,
+    pub color: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CalendarEvent {
@@ -107,6 +691,46 @@ pub struct EventDateTime {
     pub time_zone: Option<String>,
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AgendaItemKind {
+    Event,
+    Task,
+}
+
+/// A calendar event or dated task on the agenda. Times are RFC 3339 in the
+/// dashboard's local timezone
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgendaItem {
+    pub kind: AgendaItemKind,
+    pub id: String,
+    pub title: String,
+    pub start: String,
+    /// Exclusive; whole-day items end at the following midnight
+    pub end: String,
+    pub all_day: bool,
+    /// Timezone the event or task was created in, if it has one
+    pub time_zone: Option<String>,
+    /// Calendar color for events
+    pub color: Option<String>,
+    /// Calendar or project name
+    pub source: String,
+    pub event: Option<CalendarEvent>,
+    pub task: Option<Task>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Agenda {
+    pub items: Vec<AgendaItem>,
+    pub start: String,
+    pub end: String,
+    /// Sources that failed to load, e.g. "Tasks: ..."
+    pub errors: Vec<String>,
+    pub last_updated: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CalendarListEntry {
@@ -0,0 +1,128 @@
+use super::{Portfolio, PortfolioHolding, SplitEvent};
+use crate::config::{Lot, StocksConfig};
+use chrono::Local;
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+fn percent(part: f64, whole: f64) -> f64 {
+    if whole > 0.0 {
+        round2(part / whole * 100.0)
+    } else {
+        0.0
+    }
+}
+
+/// Lot on today's share basis: a lot bought before a split holds more (or
+/// fewer) shares now, each at a proportionally lower (or higher) cost
+fn split_adjusted(lot: &Lot, splits: &[SplitEvent]) -> (f64, f64) {
+    let ratio: f64 = splits
+        .iter()
+        .filter(|split| {
+            lot.date
+                .as_deref()
+                .is_some_and(|date| date < split.date.as_str())
+        })
+        .map(|split| split.numerator / split.denominator)
+        .filter(|ratio| ratio.is_finite() && *ratio > 0.0)
+        .product();
+
+    (lot.shares * ratio, lot.cost_basis / ratio)
+}
+
+pub async fn fetch_portfolio(config: &StocksConfig) -> Result<Portfolio, String> {
+    let holdings = &config.holdings;
+    let base_currency = config.base_currency.as_deref();
+    let tickers: Vec<String> = holdings.iter().map(|h| h.ticker.clone()).collect();
+    let stocks = super::stocks::fetch_stocks(&tickers, config).await?.stocks;
+
+    let mut positions = Vec::new();
+
+    for holding in holdings {
+        let Some(stock) = stocks.iter().find(|s| s.ticker == holding.ticker) else {
+            log::warn!("Portfolio: No price for '{}', skipping", holding.ticker);
+            continue;
+        };
+
+        let splits = &stock.corporate_actions.splits;
+        let lots: Vec<(f64, f64)> = holding
+            .lots
+            .iter()
+            .map(|lot| split_adjusted(lot, splits))
+            .collect();
+        let shares: f64 = lots.iter().map(|(shares, _)| shares).sum();
+        let cost_basis: f64 = lots.iter().map(|(shares, cost)| shares * cost).sum();
+        let market_value = shares * stock.price;
+        let unrealized_gain = market_value - cost_basis;
+        let day_change = shares * stock.change;
+        let annual_income = stock
+            .corporate_actions
+            .trailing_annual_dividend
+            .map(|dividend| shares * dividend);
+
+        // Without a usable rate, the position can't be added to base totals
+        let fx_rate = match (base_currency, stock.fx_rate) {
+            (None, _) => 1.0,
+            (Some(_), Some(rate)) => rate,
+            (Some(base), None) => {
+                log::warn!(
+                    "Portfolio: No {} rate for '{}', skipping",
+                    base,
+                    holding.ticker
+                );
+                continue;
+            }
+        };
+
+        positions.push(PortfolioHolding {
+            ticker: holding.ticker.clone(),
+            name: stock.name.clone(),
+            currency: stock.currency.clone(),
+            currency_code: stock.currency_code.clone(),
+            shares,
+            price: stock.price,
+            average_cost: if shares > 0.0 {
+                round2(cost_basis / shares)
+            } else {
+                0.0
+            },
+            cost_basis: round2(cost_basis),
+            market_value: round2(market_value),
+            day_change: round2(day_change),
+            unrealized_gain: round2(unrealized_gain),
+            unrealized_gain_percent: percent(unrealized_gain, cost_basis),
+            fx_rate,
+            market_value_base: round2(market_value * fx_rate),
+            day_change_base: round2(day_change * fx_rate),
+            unrealized_gain_base: round2(unrealized_gain * fx_rate),
+            annual_income: annual_income.map(round2),
+            annual_income_base: annual_income.map(|income| round2(income * fx_rate)),
+            yield_on_cost: annual_income.map(|income| percent(income, cost_basis)),
+            weight: 0.0,
+        });
+    }
+
+    // Cost basis is converted at today's rate, so FX moves show up as gains
+    let total_market_value: f64 = positions.iter().map(|p| p.market_value_base).sum();
+    let total_cost_basis: f64 = positions.iter().map(|p| p.cost_basis * p.fx_rate).sum();
+    let total_day_change: f64 = positions.iter().map(|p| p.day_change_base).sum();
+    let total_annual_income: f64 = positions.iter().filter_map(|p| p.annual_income_base).sum();
+    let total_unrealized_gain = total_market_value - total_cost_basis;
+
+    for position in &mut positions {
+        position.weight = percent(position.market_value_base, total_market_value);
+    }
+
+    Ok(Portfolio {
+        holdings: positions,
+        base_currency: base_currency.map(|c| c.to_uppercase()),
+        total_market_value: round2(total_market_value),
+        total_cost_basis: round2(total_cost_basis),
+        total_day_change: round2(total_day_change),
+        total_unrealized_gain: round2(total_unrealized_gain),
+        total_unrealized_gain_percent: percent(total_unrealized_gain, total_cost_basis),
+        total_annual_income: round2(total_annual_income),
+        last_updated: Local::now().to_rfc3339(),
+    })
+}
@@ -0,0 +1,88 @@
+use super::{MarketDataProvider, UNKNOWN_SYMBOL};
+use crate::api::{ChartRange, OhlcPoint, StockChart, StockData};
+use crate::config::{get_config_dir, StocksConfig};
+use chrono::{Duration, Local};
+use reqwest::Client;
+use std::fs;
+use std::path::PathBuf;
+
+/// Reads serialized `StockData` quotes from disk, for working on the cards offline
+pub struct FixtureProvider;
+
+fn fixture_path(config: &StocksConfig) -> Result<PathBuf, String> {
+    match &config.fixture_path {
+        Some(path) => Ok(PathBuf::from(path)),
+        None => get_config_dir()
+            .map(|p| p.join("stocks.fixture.json"))
+            .map_err(|e| e.to_string()),
+    }
+}
+
+fn load_quote(config: &StocksConfig, ticker: &str) -> Result<StockData, String> {
+    let path = fixture_path(config)?;
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read stocks fixture {}: {}", path.display(), e))?;
+
+    let stocks: Vec<StockData> = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse stocks fixture {}: {}", path.display(), e))?;
+
+    stocks
+        .into_iter()
+        .find(|s| s.ticker.eq_ignore_ascii_case(ticker))
+        .ok_or_else(|| format!("{} {} (not in the stocks fixture)", UNKNOWN_SYMBOL, ticker))
+}
+
+impl MarketDataProvider for FixtureProvider {
+    async fn fetch_quote(
+        &self,
+        _client: &Client,
+        config: &StocksConfig,
+        ticker: &str,
+    ) -> Result<StockData, String> {
+        let mut stock = load_quote(config, ticker)?;
+        stock.last_updated = Local::now().to_rfc3339();
+        Ok(stock)
+    }
+
+    /// The sparkline doubles as a daily chart ending today, whatever the range
+    async fn fetch_chart(
+        &self,
+        _client: &Client,
+        config: &StocksConfig,
+        ticker: &str,
+        range: ChartRange,
+    ) -> Result<StockChart, String> {
+        let stock = load_quote(config, ticker)?;
+        let today = Local::now().date_naive();
+        let count = stock.sparkline_data.len() as i64;
+
+        let points = stock
+            .sparkline_data
+            .iter()
+            .enumerate()
+            .filter_map(|(i, &close)| {
+                let date = today - Duration::days(count - 1 - i as i64);
+                Some(OhlcPoint {
+                    timestamp: date.and_hms_opt(0, 0, 0)?.and_utc().timestamp(),
+                    open: close,
+                    high: close,
+                    low: close,
+                    close,
+                    volume: None,
+                })
+            })
+            .collect();
+
+        Ok(StockChart {
+            ticker: stock.ticker,
+            range,
+            interval: "1d".to_string(),
+            currency_code: stock.currency_code,
+            price_hint: stock.price_hint,
+            previous_close: Some(stock.price - stock.change),
+            points,
+            last_updated: Local::now().to_rfc3339(),
+        })
+    }
+}
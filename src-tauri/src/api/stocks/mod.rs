@@ -0,0 +1,394 @@
+mod fixture;
+mod stooq;
+mod yahoo;
+
+use super::{
+    ChartRange, FetchStatus, SessionState, StockChart, StockData, StocksData, SymbolMatch,
+    TickerStatus, TickerValidation,
+};
+use crate::config::{AssetKind, MarketDataProviderKind, StocksConfig};
+use chrono::Local;
+use reqwest::Client;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+pub use fixture::FixtureProvider;
+pub use stooq::Stooq;
+pub use yahoo::Yahoo;
+
+/// Prefix for errors meaning the provider has never heard of the symbol, as
+/// opposed to a network or parse failure
+const UNKNOWN_SYMBOL: &str = "Unknown symbol";
+
+// Keep a slow provider response from holding up the whole card
+const MAX_CONCURRENT_REQUESTS: usize = 4;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A source of quotes and price history, mapped into the shared `StockData`.
+/// Tickers are always Yahoo-style symbols (e.g. "VEQT.TO", "CADUSD=X") and
+/// each provider maps them to its own naming
+pub trait MarketDataProvider {
+    async fn fetch_quote(
+        &self,
+        client: &Client,
+        config: &StocksConfig,
+        ticker: &str,
+    ) -> Result<StockData, String>;
+
+    async fn fetch_chart(
+        &self,
+        client: &Client,
+        config: &StocksConfig,
+        ticker: &str,
+        range: ChartRange,
+    ) -> Result<StockChart, String>;
+}
+
+/// Display symbol for an ISO currency code, falling back to the code itself
+fn get_currency_symbol(currency: &str) -> String {
+    let symbol = match currency {
+        "USD" => "$",
+        "EUR" => "€",
+        "JPY" | "CNY" => "¥",
+        "CAD" => "C$",
+        "AUD" => "A$",
+        "GBP" => "£",
+        "CHF" => "Fr",
+        "NZD" => "N$",
+        "INR" => "₹",
+        "BRL" => "R$",
+        "RUB" => "₽",
+        "TRY" => "₺",
+        "ZAR" => "R",
+        "KRW" => "₩",
+        "HKD" => "HK$",
+        "SGD" => "S$",
+        "SEK" | "NOK" | "DKK" => "kr",
+        "PLN" => "zł",
+        "PHP" => "₱",
+        _ => return format!("{} ", currency),
+    };
+    symbol.to_string()
+}
+
+/// Decimals that suit the asset type when the watchlist doesn't set them
+fn default_decimals(kind: AssetKind, stock: &StockData) -> i32 {
+    match kind {
+        AssetKind::Fx if stock.currency_code == "JPY" => 2,
+        AssetKind::Fx => 4,
+        // Small coins trade in fractions of a cent
+        AssetKind::Crypto if stock.price < 1.0 => 6,
+        AssetKind::Crypto if stock.price < 10.0 => 4,
+        AssetKind::Crypto | AssetKind::Index => 2,
+        AssetKind::Equity | AssetKind::Commodity => stock.price_hint,
+    }
+}
+
+/// Apply the watchlist's type, display name and decimal rules to a quote
+fn apply_asset_rules(config: &StocksConfig, stock: &mut StockData) {
+    let entry = config.entry(&stock.ticker);
+    let kind = entry.map_or_else(|| AssetKind::infer(&stock.ticker), |e| e.kind());
+
+    stock.asset_kind = kind;
+    if let Some(name) = entry.and_then(|e| e.name()) {
+        stock.name = name.to_string();
+    }
+
+    stock.price_hint = entry
+        .and_then(|e| e.decimals())
+        .unwrap_or_else(|| default_decimals(kind, stock));
+    let multiplier = 10_f64.powi(stock.price_hint);
+    stock.change = (stock.change * multiplier).round() / multiplier;
+
+    match kind {
+        // Index levels are points and FX rates are ratios, not prices
+        AssetKind::Index | AssetKind::Fx => stock.currency = String::new(),
+        // Crypto never closes, so there's no pre/post-market or next open
+        AssetKind::Crypto => {
+            stock.market.state = SessionState::Regular;
+            stock.market.next_open = None;
+            stock.extended_hours = None;
+        }
+        AssetKind::Equity | AssetKind::Commodity => {}
+    }
+}
+
+/// Change from `previous` to `price`, rounded to the quote's precision
+fn price_change(price: f64, previous: f64, price_hint: i32) -> (f64, f64) {
+    let multiplier = 10_f64.powi(price_hint);
+    let change = price - previous;
+    let change_percent = if previous > 0.0 {
+        (change / previous) * 100.0
+    } else {
+        0.0
+    };
+
+    (
+        (change * multiplier).round() / multiplier,
+        (change_percent * 100.0).round() / 100.0,
+    )
+}
+
+fn provider_for(config: &StocksConfig, ticker: &str) -> MarketDataProviderKind {
+    config
+        .ticker_providers
+        .get(ticker)
+        .copied()
+        .unwrap_or(config.provider)
+}
+
+async fn fetch_quote(
+    client: &Client,
+    config: &StocksConfig,
+    provider: MarketDataProviderKind,
+    ticker: &str,
+) -> Result<StockData, String> {
+    let mut stock = match provider {
+        MarketDataProviderKind::Yahoo => Yahoo.fetch_quote(client, config, ticker).await,
+        MarketDataProviderKind::Stooq => Stooq.fetch_quote(client, config, ticker).await,
+        MarketDataProviderKind::Fixture => {
+            FixtureProvider.fetch_quote(client, config, ticker).await
+        }
+    }?;
+
+    apply_asset_rules(config, &mut stock);
+
+    Ok(stock)
+}
+
+pub async fn fetch_stock_chart(
+    config: &StocksConfig,
+    ticker: &str,
+    range: ChartRange,
+) -> Result<StockChart, String> {
+    let client = Client::new();
+    let mut chart = match provider_for(config, ticker) {
+        MarketDataProviderKind::Yahoo => Yahoo.fetch_chart(&client, config, ticker, range).await,
+        MarketDataProviderKind::Stooq => Stooq.fetch_chart(&client, config, ticker, range).await,
+        MarketDataProviderKind::Fixture => {
+            FixtureProvider
+                .fetch_chart(&client, config, ticker, range)
+                .await
+        }
+    }?;
+
+    if let Some(decimals) = config.entry(ticker).and_then(|e| e.decimals()) {
+        chart.price_hint = decimals;
+    }
+
+    Ok(chart)
+}
+
+/// Price of one unit of `from` in `to`, via FX symbols such as "CADUSD=X"
+async fn fetch_fx_rate(
+    client: &Client,
+    config: &StocksConfig,
+    from: &str,
+    to: &str,
+) -> Result<f64, String> {
+    if from == to {
+        return Ok(1.0);
+    }
+
+    let pair = format!("{}{}=X", from, to);
+    fetch_quote(client, config, provider_for(config, &pair), &pair)
+        .await
+        .map(|quote| quote.price)
+}
+
+/// Fill in the base currency fields, leaving them empty where no FX rate is available
+async fn convert_to_base(
+    client: &Client,
+    config: &StocksConfig,
+    stocks: &mut [StockData],
+    base_currency: &str,
+) {
+    let base_currency = base_currency.to_uppercase();
+    let mut rates: HashMap<String, Option<f64>> = HashMap::new();
+
+    for stock in stocks.iter_mut() {
+        let rate = match rates.get(&stock.currency_code) {
+            Some(rate) => *rate,
+            None => {
+                let rate = match fetch_fx_rate(client, config, &stock.currency_code, &base_currency)
+                    .await
+                {
+                    Ok(rate) => Some(rate),
+                    Err(e) => {
+                        log::warn!("Stocks: No FX rate for {}: {}", stock.currency_code, e);
+                        None
+                    }
+                };
+                rates.insert(stock.currency_code.clone(), rate);
+                rate
+            }
+        };
+
+        if let Some(rate) = rate {
+            let multiplier = 10_f64.powi(stock.price_hint);
+            stock.base_currency = Some(base_currency.clone());
+            stock.fx_rate = Some(rate);
+            stock.price_in_base = Some((stock.price * rate * multiplier).round() / multiplier);
+            stock.change_in_base = Some((stock.change * rate * multiplier).round() / multiplier);
+        }
+    }
+}
+
+/// Refresh often while any market is trading, slowly once they're all closed
+fn refresh_interval(stocks: &[StockData], config: &StocksConfig) -> u32 {
+    if stocks
+        .iter()
+        .any(|s| s.market.state != SessionState::Closed)
+    {
+        config.refresh_interval_minutes
+    } else {
+        config.closed_refresh_interval_minutes
+    }
+}
+
+/// Fetch quotes for every ticker concurrently, keeping the input order
+async fn fetch_quotes(
+    client: &Client,
+    config: &StocksConfig,
+    tickers: &[String],
+) -> Result<Vec<Result<StockData, TickerStatus>>, String> {
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_REQUESTS));
+    let mut tasks = JoinSet::new();
+
+    for (index, ticker) in tickers.iter().enumerate() {
+        let client = client.clone();
+        let config = config.clone();
+        let semaphore = semaphore.clone();
+        let ticker = ticker.clone();
+
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let provider = provider_for(&config, &ticker);
+            let result = tokio::time::timeout(
+                REQUEST_TIMEOUT,
+                fetch_quote(&client, &config, provider, &ticker),
+            )
+            .await;
+            (index, result)
+        });
+    }
+
+    let mut results: Vec<Option<Result<StockData, TickerStatus>>> = vec![None; tickers.len()];
+
+    while let Some(joined) = tasks.join_next().await {
+        let (index, result) = joined.map_err(|e| format!("Stock fetch task failed: {}", e))?;
+        let ticker = &tickers[index];
+
+        results[index] = Some(match result {
+            Ok(Ok(stock)) => Ok(stock),
+            Ok(Err(e)) => Err(TickerStatus {
+                ticker: ticker.clone(),
+                status: if e.starts_with(UNKNOWN_SYMBOL) {
+                    FetchStatus::NotFound
+                } else {
+                    FetchStatus::Error
+                },
+                error: Some(e),
+            }),
+            Err(_) => Err(TickerStatus {
+                ticker: ticker.clone(),
+                status: FetchStatus::Timeout,
+                error: Some(format!(
+                    "Timed out after {}s fetching {}",
+                    REQUEST_TIMEOUT.as_secs(),
+                    ticker
+                )),
+            }),
+        });
+    }
+
+    Ok(results.into_iter().flatten().collect())
+}
+
+/// Check that each ticker resolves with its configured provider
+pub async fn validate_tickers(
+    tickers: &[String],
+    config: &StocksConfig,
+) -> Result<Vec<TickerValidation>, String> {
+    let client = Client::new();
+    let results = fetch_quotes(&client, config, tickers).await?;
+
+    Ok(results
+        .into_iter()
+        .map(|result| match result {
+            Ok(stock) => TickerValidation {
+                ticker: stock.ticker,
+                status: FetchStatus::Ok,
+                name: Some(stock.name),
+                asset_kind: Some(stock.asset_kind),
+                currency_code: Some(stock.currency_code),
+                error: None,
+            },
+            Err(status) => TickerValidation {
+                ticker: status.ticker,
+                status: status.status,
+                name: None,
+                asset_kind: None,
+                currency_code: None,
+                error: status.error,
+            },
+        })
+        .collect())
+}
+
+pub async fn search_symbols(query: &str) -> Result<Vec<SymbolMatch>, String> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let client = Client::new();
+    yahoo::search(&client, query).await
+}
+
+pub async fn fetch_stocks(tickers: &[String], config: &StocksConfig) -> Result<StocksData, String> {
+    let client = Client::new();
+    let results = fetch_quotes(&client, config, tickers).await?;
+
+    let mut stocks = Vec::new();
+    let mut statuses = Vec::new();
+    let mut errors = Vec::new();
+
+    for result in results {
+        match result {
+            Ok(stock) => {
+                statuses.push(TickerStatus {
+                    ticker: stock.ticker.clone(),
+                    status: FetchStatus::Ok,
+                    error: None,
+                });
+                stocks.push(stock);
+            }
+            Err(status) => {
+                if let Some(e) = &status.error {
+                    log::warn!("Stocks: {}", e);
+                    errors.push(e.clone());
+                }
+                statuses.push(status);
+            }
+        }
+    }
+
+    if stocks.is_empty() && !errors.is_empty() {
+        return Err(format!("Failed to fetch stocks: {}", errors.join(", ")));
+    }
+
+    if let Some(base_currency) = &config.base_currency {
+        convert_to_base(&client, config, &mut stocks, base_currency).await;
+    }
+
+    Ok(StocksData {
+        refresh_interval_minutes: refresh_interval(&stocks, config),
+        stocks,
+        statuses,
+        last_updated: Local::now().to_rfc3339(),
+    })
+}
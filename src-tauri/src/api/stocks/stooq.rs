@@ -0,0 +1,222 @@
+use super::{get_currency_symbol, price_change, MarketDataProvider, UNKNOWN_SYMBOL};
+use crate::api::{
+    ChartRange, CorporateActions, MarketSession, OhlcPoint, SessionState, StockChart, StockData,
+};
+use crate::config::{AssetKind, StocksConfig};
+use chrono::{Duration, Local, NaiveDate};
+use reqwest::Client;
+
+const STOOQ_BASE: &str = "https://stooq.com/q/d/l/";
+
+/// Stooq CSV downloads. No API key, but prices are end-of-day (or delayed)
+/// and there is no intraday history or session info
+pub struct Stooq;
+
+/// One row of a Stooq history CSV
+struct Bar {
+    date: NaiveDate,
+    open: f64,
+    high: f64,
+    low: f64,
+    close: f64,
+    volume: Option<u64>,
+}
+
+/// Map a Yahoo-style symbol to Stooq's naming ("TRI" -> "tri.us", "CADUSD=X" -> "cadusd")
+fn stooq_symbol(ticker: &str) -> String {
+    if let Some(pair) = ticker.strip_suffix("=X") {
+        return pair.to_lowercase();
+    }
+
+    match ticker.rsplit_once('.') {
+        None => format!("{}.us", ticker.to_lowercase()),
+        Some((symbol, suffix)) => {
+            let market = match suffix.to_uppercase().as_str() {
+                "L" => "uk",
+                "DE" | "F" => "de",
+                "T" => "jp",
+                "HK" => "hk",
+                other => return format!("{}.{}", symbol, other).to_lowercase(),
+            };
+            format!("{}.{}", symbol.to_lowercase(), market)
+        }
+    }
+}
+
+/// Stooq doesn't report a currency, so infer it from the market. Markets we
+/// don't know are an error rather than a guess
+fn currency_for(symbol: &str) -> Result<String, String> {
+    if !symbol.contains('.') && symbol.len() == 6 {
+        // FX pair, quoted in the second currency
+        return Ok(symbol[3..].to_uppercase());
+    }
+
+    let currency = match symbol.rsplit_once('.').map(|(_, market)| market) {
+        Some("us") => "USD",
+        Some("uk") => "GBP",
+        Some("de") => "EUR",
+        Some("jp") => "JPY",
+        Some("hk") => "HKD",
+        Some("to") => "CAD",
+        _ => return Err(format!("Unknown currency for {} on Stooq", symbol)),
+    };
+    Ok(currency.to_string())
+}
+
+fn parse_csv(ticker: &str, content: &str) -> Result<Vec<Bar>, String> {
+    let mut lines = content.lines();
+    let header = lines.next().unwrap_or_default();
+    if !header.starts_with("Date") {
+        // Unknown symbols come back as a plain "No data" body
+        return Err(format!("{} {} on Stooq", UNKNOWN_SYMBOL, ticker));
+    }
+
+    let bars = lines
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split(',').collect();
+            let number = |i: usize| fields.get(i).and_then(|v| v.trim().parse::<f64>().ok());
+            Some(Bar {
+                date: NaiveDate::parse_from_str(fields.first()?, "%Y-%m-%d").ok()?,
+                open: number(1)?,
+                high: number(2)?,
+                low: number(3)?,
+                close: number(4)?,
+                // FX rows have no volume column
+                volume: fields.get(5).and_then(|v| v.trim().parse::<u64>().ok()),
+            })
+        })
+        .collect();
+
+    Ok(bars)
+}
+
+async fn fetch_history(
+    client: &Client,
+    ticker: &str,
+    since: NaiveDate,
+    interval: &str,
+) -> Result<Vec<Bar>, String> {
+    let url = format!(
+        "{}?s={}&i={}&d1={}&d2={}",
+        STOOQ_BASE,
+        urlencoding::encode(&stooq_symbol(ticker)),
+        interval,
+        since.format("%Y%m%d"),
+        Local::now().format("%Y%m%d")
+    );
+
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Stooq request failed for {}: {}", ticker, e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Stooq error for {}: {}", ticker, response.status()));
+    }
+
+    let content = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read Stooq response for {}: {}", ticker, e))?;
+
+    parse_csv(ticker, &content)
+}
+
+impl MarketDataProvider for Stooq {
+    async fn fetch_quote(
+        &self,
+        client: &Client,
+        _config: &StocksConfig,
+        ticker: &str,
+    ) -> Result<StockData, String> {
+        let currency = currency_for(&stooq_symbol(ticker))?;
+        // Six weeks of daily bars covers the 21-point sparkline
+        let since = Local::now().date_naive() - Duration::days(42);
+        let bars = fetch_history(client, ticker, since, "d").await?;
+
+        let closes: Vec<f64> = bars.iter().map(|b| b.close).collect();
+        let price = *closes
+            .last()
+            .ok_or_else(|| format!("No data returned for {}", ticker))?;
+        let previous = closes.len().checked_sub(2).map_or(price, |i| closes[i]);
+
+        let decimals = if ticker.ends_with("=X") { 4 } else { 2 };
+        let (change, change_percent) = price_change(price, previous, decimals);
+
+        Ok(StockData {
+            ticker: ticker.to_string(),
+            name: ticker.to_string(),
+            asset_kind: AssetKind::infer(ticker),
+            price,
+            change,
+            change_percent,
+            currency: get_currency_symbol(&currency),
+            currency_code: currency,
+            base_currency: None,
+            fx_rate: None,
+            price_in_base: None,
+            change_in_base: None,
+            sparkline_data: closes.iter().rev().take(21).rev().cloned().collect(),
+            price_hint: decimals,
+            fifty_two_week_high: None,
+            fifty_two_week_low: None,
+            corporate_actions: CorporateActions::default(),
+            market: MarketSession {
+                state: SessionState::Unknown,
+                exchange_timezone: None,
+                regular_market_time: None,
+                next_open: None,
+            },
+            extended_hours: None,
+            last_updated: Local::now().to_rfc3339(),
+        })
+    }
+
+    async fn fetch_chart(
+        &self,
+        client: &Client,
+        _config: &StocksConfig,
+        ticker: &str,
+        range: ChartRange,
+    ) -> Result<StockChart, String> {
+        let (days, interval) = match range {
+            ChartRange::OneDay | ChartRange::FiveDays => {
+                return Err(format!("Stooq has no intraday data for {}", ticker))
+            }
+            ChartRange::OneMonth => (31, "d"),
+            ChartRange::SixMonths => (183, "d"),
+            ChartRange::OneYear => (365, "d"),
+            ChartRange::FiveYears => (5 * 365, "w"),
+        };
+
+        let currency = currency_for(&stooq_symbol(ticker))?;
+        let since = Local::now().date_naive() - Duration::days(days);
+        let bars = fetch_history(client, ticker, since, interval).await?;
+
+        let points = bars
+            .iter()
+            .filter_map(|bar| {
+                Some(OhlcPoint {
+                    timestamp: bar.date.and_hms_opt(0, 0, 0)?.and_utc().timestamp(),
+                    open: bar.open,
+                    high: bar.high,
+                    low: bar.low,
+                    close: bar.close,
+                    volume: bar.volume,
+                })
+            })
+            .collect();
+
+        Ok(StockChart {
+            ticker: ticker.to_string(),
+            range,
+            interval: if interval == "w" { "1wk" } else { "1d" }.to_string(),
+            currency_code: currency,
+            price_hint: if ticker.ends_with("=X") { 4 } else { 2 },
+            previous_close: None,
+            points,
+            last_updated: Local::now().to_rfc3339(),
+        })
+    }
+}
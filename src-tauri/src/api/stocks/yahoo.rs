@@ -0,0 +1,630 @@
+use super::{get_currency_symbol, price_change, MarketDataProvider, UNKNOWN_SYMBOL};
+use crate::api::{
+    ChartRange, CorporateActions, DividendEvent, ExtendedHoursQuote, MarketSession, OhlcPoint,
+    SessionState, SplitEvent, StockChart, StockData, SymbolMatch,
+};
+use crate::config::{AssetKind, StocksConfig};
+use chrono::{DateTime, Datelike, FixedOffset, Local, Utc, Weekday};
+use reqwest::Client;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::task::JoinSet;
+
+const YAHOO_BASE: &str = "https://query1.finance.yahoo.com/v8/finance/chart";
+const YAHOO_SEARCH_URL: &str = "https://query1.finance.yahoo.com/v1/finance/search";
+const USER_AGENT: &str = "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36";
+const MAX_SEARCH_RESULTS: usize = 8;
+const SEARCH_LOOKUP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Yahoo Finance chart API. Unofficial, and only answers browser-like User-Agents
+pub struct Yahoo;
+
+#[derive(Debug, Deserialize)]
+struct YahooResponse {
+    chart: ChartData,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChartData {
+    result: Option<Vec<ChartResult>>,
+    error: Option<ChartError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChartResult {
+    meta: ChartMeta,
+    #[serde(default)]
+    timestamp: Vec<i64>,
+    indicators: Indicators,
+    #[serde(default)]
+    events: ChartEvents,
+}
+
+/// Keyed by the event's timestamp
+#[derive(Debug, Default, Deserialize)]
+struct ChartEvents {
+    #[serde(default)]
+    dividends: HashMap<String, DividendData>,
+    #[serde(default)]
+    splits: HashMap<String, SplitData>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct DividendData {
+    amount: f64,
+    date: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct SplitData {
+    date: i64,
+    numerator: f64,
+    denominator: f64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChartMeta {
+    regular_market_price: f64,
+    #[serde(default)]
+    chart_previous_close: f64,
+    currency: Option<String>,
+    short_name: Option<String>,
+    #[serde(default = "default_price_hint")]
+    price_hint: i32,
+    fifty_two_week_high: Option<f64>,
+    fifty_two_week_low: Option<f64>,
+    exchange_timezone_name: Option<String>,
+    regular_market_time: Option<i64>,
+    current_trading_period: Option<TradingPeriods>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TradingPeriods {
+    pre: TradingPeriod,
+    regular: TradingPeriod,
+    post: TradingPeriod,
+}
+
+#[derive(Debug, Deserialize)]
+struct TradingPeriod {
+    start: i64,
+    end: i64,
+    gmtoffset: i32,
+}
+
+fn default_price_hint() -> i32 {
+    2
+}
+
+#[derive(Debug, Deserialize)]
+struct Indicators {
+    quote: Vec<QuoteData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct QuoteData {
+    #[serde(default)]
+    open: Vec<Option<f64>>,
+    #[serde(default)]
+    high: Vec<Option<f64>>,
+    #[serde(default)]
+    low: Vec<Option<f64>>,
+    close: Vec<Option<f64>>,
+    #[serde(default)]
+    volume: Vec<Option<u64>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChartError {
+    code: String,
+    description: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    #[serde(default)]
+    quotes: Vec<SearchQuote>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchQuote {
+    symbol: String,
+    #[serde(rename = "shortname")]
+    short_name: Option<String>,
+    #[serde(rename = "longname")]
+    long_name: Option<String>,
+    #[serde(rename = "exchDisp")]
+    exchange_display: Option<String>,
+    exchange: Option<String>,
+    quote_type: Option<String>,
+    type_disp: Option<String>,
+    /// False for entries that aren't tradable symbols (e.g. private companies)
+    #[serde(default)]
+    is_yahoo_finance: bool,
+}
+
+/// Yahoo's range and bar size for each chart range
+fn chart_params(range: ChartRange) -> (&'static str, &'static str) {
+    match range {
+        ChartRange::OneDay => ("1d", "5m"),
+        ChartRange::FiveDays => ("5d", "15m"),
+        ChartRange::OneMonth => ("1mo", "1d"),
+        ChartRange::SixMonths => ("6mo", "1d"),
+        ChartRange::OneYear => ("1y", "1d"),
+        ChartRange::FiveYears => ("5y", "1wk"),
+    }
+}
+
+async fn fetch_chart_result(
+    client: &Client,
+    ticker: &str,
+    range: &str,
+    interval: &str,
+    include_pre_post: bool,
+) -> Result<ChartResult, String> {
+    let url = format!(
+        "{}/{}?interval={}&range={}&includePrePost={}&events=div,split",
+        YAHOO_BASE, ticker, interval, range, include_pre_post
+    );
+
+    let response = client
+        .get(&url)
+        .header("User-Agent", USER_AGENT)
+        .send()
+        .await
+        .map_err(|e| format!("Stock API request failed for {}: {}", ticker, e))?;
+
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Err(format!("{} {}", UNKNOWN_SYMBOL, ticker));
+    }
+
+    if !response.status().is_success() {
+        return Err(format!("Stock API error for {}: {}", ticker, response.status()));
+    }
+
+    let data: YahooResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse stock response for {}: {}", ticker, e))?;
+
+    if let Some(error) = data.chart.error {
+        if error.code == "Not Found" {
+            return Err(format!("{} {}", UNKNOWN_SYMBOL, ticker));
+        }
+        return Err(format!("Yahoo API error for {}: {}", ticker, error.description));
+    }
+
+    data.chart
+        .result
+        .and_then(|r| r.into_iter().next())
+        .ok_or_else(|| format!("No data returned for {}", ticker))
+}
+
+fn format_timestamp(timestamp: i64) -> Option<String> {
+    DateTime::<Utc>::from_timestamp(timestamp, 0).map(|t| t.with_timezone(&Local).to_rfc3339())
+}
+
+/// Work out the session from Yahoo's `currentTradingPeriod`, which describes
+/// the current (or most recent) trading day
+fn market_session(meta: &ChartMeta, now: i64) -> MarketSession {
+    let Some(periods) = &meta.current_trading_period else {
+        return MarketSession {
+            state: SessionState::Closed,
+            exchange_timezone: meta.exchange_timezone_name.clone(),
+            regular_market_time: meta.regular_market_time.and_then(format_timestamp),
+            next_open: None,
+        };
+    };
+
+    let within = |p: &TradingPeriod| p.start <= now && now < p.end;
+    let state = if within(&periods.regular) {
+        SessionState::Regular
+    } else if within(&periods.pre) {
+        SessionState::Pre
+    } else if within(&periods.post) {
+        SessionState::Post
+    } else {
+        SessionState::Closed
+    };
+
+    // Today's open if it's still ahead, otherwise step forward a day at a
+    // time (in exchange local time) until we land on a weekday
+    let regular = &periods.regular;
+    let next_open = if now < regular.start {
+        Some(regular.start)
+    } else {
+        FixedOffset::east_opt(regular.gmtoffset).and_then(|offset| {
+            (1..=7)
+                .map(|days| regular.start + days * 86_400)
+                .find(|&start| {
+                    DateTime::<Utc>::from_timestamp(start, 0)
+                        .map(|t| t.with_timezone(&offset).weekday())
+                        .is_some_and(|day| day != Weekday::Sat && day != Weekday::Sun)
+                })
+        })
+    };
+
+    MarketSession {
+        state,
+        exchange_timezone: meta.exchange_timezone_name.clone(),
+        regular_market_time: meta.regular_market_time.and_then(format_timestamp),
+        next_open: next_open.and_then(format_timestamp),
+    }
+}
+
+/// Latest pre/post-market trade from today's intraday bars
+async fn fetch_extended_hours(
+    client: &Client,
+    ticker: &str,
+    session: SessionState,
+    regular_price: f64,
+) -> Result<Option<ExtendedHoursQuote>, String> {
+    let result = fetch_chart_result(client, ticker, "1d", "5m", true).await?;
+
+    let price = result
+        .indicators
+        .quote
+        .first()
+        .and_then(|q| q.close.iter().rev().find_map(|p| *p));
+
+    Ok(price.map(|price| {
+        let change = price - regular_price;
+        ExtendedHoursQuote {
+            session,
+            price,
+            change: (change * 10_000.0).round() / 10_000.0,
+            change_percent: if regular_price > 0.0 {
+                (change / regular_price * 100.0 * 100.0).round() / 100.0
+            } else {
+                0.0
+            },
+        }
+    }))
+}
+
+fn format_date(timestamp: i64) -> String {
+    DateTime::<Utc>::from_timestamp(timestamp, 0)
+        .map(|t| t.with_timezone(&Local).format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+/// Per-bar multipliers that put every price on today's share basis. Yahoo's
+/// closes are usually split-adjusted already, so a split is only applied when
+/// the price actually jumps by the split ratio across it
+fn split_factors(result: &ChartResult, closes: &[Option<f64>]) -> Vec<f64> {
+    let mut factors = vec![1.0; closes.len()];
+
+    for split in result.events.splits.values() {
+        let ratio = split.numerator / split.denominator;
+        if !ratio.is_finite() || ratio <= 0.0 || ratio == 1.0 {
+            continue;
+        }
+
+        let Some(boundary) = result.timestamp.iter().position(|&t| t >= split.date) else {
+            continue;
+        };
+        let boundary = boundary.min(closes.len());
+        let before = closes[..boundary].iter().rev().find_map(|c| *c);
+        let after = closes[boundary..].iter().find_map(|c| *c);
+
+        if let (Some(before), Some(after)) = (before, after) {
+            // Compare in log space so 4:1 and 1:4 splits are treated alike
+            let jump = (before / after).ln();
+            if (jump - ratio.ln()).abs() < jump.abs() {
+                for factor in &mut factors[..boundary] {
+                    *factor /= ratio;
+                }
+            }
+        }
+    }
+
+    factors
+}
+
+/// Closing prices with splits adjusted for, keeping gaps
+fn adjusted_closes(result: &ChartResult) -> Vec<Option<f64>> {
+    let closes = result
+        .indicators
+        .quote
+        .first()
+        .map(|q| q.close.clone())
+        .unwrap_or_default();
+    let factors = split_factors(result, &closes);
+
+    closes
+        .iter()
+        .zip(factors)
+        .map(|(close, factor)| close.map(|c| c * factor))
+        .collect()
+}
+
+/// Project the next ex-dividend date from the average gap between payments
+fn estimate_next_dividend(dividends: &[DividendData]) -> Option<String> {
+    if dividends.len() < 2 {
+        return None;
+    }
+
+    let first = dividends.first()?.date;
+    let last = dividends.last()?.date;
+    let gap = (last - first) / (dividends.len() as i64 - 1);
+    if gap <= 0 {
+        return None;
+    }
+
+    let now = Utc::now().timestamp();
+    let mut next = last + gap;
+    while next < now {
+        next += gap;
+    }
+
+    Some(format_date(next))
+}
+
+fn corporate_actions(result: &ChartResult, price: f64) -> CorporateActions {
+    let mut dividends: Vec<DividendData> = result.events.dividends.values().cloned().collect();
+    dividends.sort_by_key(|d| d.date);
+
+    let mut splits: Vec<&SplitData> = result.events.splits.values().collect();
+    splits.sort_by_key(|s| s.date);
+
+    let year_ago = Utc::now().timestamp() - 365 * 86_400;
+    let trailing: f64 = dividends
+        .iter()
+        .filter(|d| d.date >= year_ago)
+        .map(|d| d.amount)
+        .sum();
+    let trailing = (trailing > 0.0).then_some((trailing * 10_000.0).round() / 10_000.0);
+
+    CorporateActions {
+        estimated_next_dividend: estimate_next_dividend(&dividends),
+        dividends: dividends
+            .iter()
+            .map(|d| DividendEvent {
+                date: format_date(d.date),
+                amount: d.amount,
+            })
+            .collect(),
+        splits: splits
+            .iter()
+            .map(|s| SplitEvent {
+                date: format_date(s.date),
+                numerator: s.numerator,
+                denominator: s.denominator,
+            })
+            .collect(),
+        trailing_annual_dividend: trailing,
+        dividend_yield: trailing
+            .filter(|_| price > 0.0)
+            .map(|t| (t / price * 100.0 * 100.0).round() / 100.0),
+    }
+}
+
+async fn fetch_quote(client: &Client, ticker: &str) -> Result<StockData, String> {
+    // A year of daily bars, so the dividend events cover the trailing 12 months
+    let result = fetch_chart_result(client, ticker, "1y", "1d", false).await?;
+    let corporate_actions = corporate_actions(&result, result.meta.regular_market_price);
+    let close_prices: Vec<f64> = adjusted_closes(&result).into_iter().flatten().collect();
+    let meta = result.meta;
+    let market = market_session(&meta, Utc::now().timestamp());
+
+    let extended_hours = match market.state {
+        SessionState::Pre | SessionState::Post => {
+            fetch_extended_hours(client, ticker, market.state, meta.regular_market_price)
+                .await
+                .unwrap_or_else(|e| {
+                    log::warn!("Stocks: No extended hours price for {}: {}", ticker, e);
+                    None
+                })
+        }
+        _ => None,
+    };
+
+    // Get sparkline data (last 21 non-null closing prices)
+    let sparkline: Vec<f64> = close_prices.iter().rev().take(21).rev().cloned().collect();
+
+    // Calculate previous price
+    let previous = if close_prices.len() >= 2 {
+        close_prices[close_prices.len() - 2]
+    } else if meta.chart_previous_close > 0.0 {
+        meta.chart_previous_close
+    } else {
+        meta.regular_market_price
+    };
+
+    let decimals = meta.price_hint;
+    // Before the open and once the post-market ends, the last two closes are
+    // the previous session's move rather than today's
+    let (change, change_percent) = match market.state {
+        SessionState::Regular | SessionState::Post => {
+            price_change(meta.regular_market_price, previous, decimals)
+        }
+        _ => (0.0, 0.0),
+    };
+
+    let currency = meta.currency.as_deref().unwrap_or("USD").to_uppercase();
+
+    Ok(StockData {
+        ticker: ticker.to_string(),
+        name: meta.short_name.unwrap_or_else(|| ticker.to_string()),
+        asset_kind: AssetKind::infer(ticker),
+        price: meta.regular_market_price,
+        change,
+        change_percent,
+        currency: get_currency_symbol(&currency),
+        currency_code: currency,
+        base_currency: None,
+        fx_rate: None,
+        price_in_base: None,
+        change_in_base: None,
+        sparkline_data: sparkline,
+        price_hint: decimals,
+        fifty_two_week_high: meta.fifty_two_week_high,
+        fifty_two_week_low: meta.fifty_two_week_low,
+        corporate_actions,
+        market,
+        extended_hours,
+        last_updated: Local::now().to_rfc3339(),
+    })
+}
+
+async fn fetch_chart(
+    client: &Client,
+    ticker: &str,
+    range: ChartRange,
+) -> Result<StockChart, String> {
+    let (yahoo_range, interval) = chart_params(range);
+    let result = fetch_chart_result(client, ticker, yahoo_range, interval, false).await?;
+
+    let quote = result.indicators.quote.first();
+    let factors = split_factors(&result, quote.map_or(&[], |q| &q.close));
+    let value_at = |values: Option<&Vec<Option<f64>>>, i: usize| {
+        let factor = factors.get(i).copied().unwrap_or(1.0);
+        values.and_then(|v| v.get(i).copied().flatten().map(|p| p * factor))
+    };
+
+    // Bars without a close (e.g. a halted interval) are skipped; missing
+    // open/high/low fall back to the close. Prices before a split are
+    // scaled to today's share basis
+    let points = result
+        .timestamp
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &timestamp)| {
+            let close = value_at(quote.map(|q| &q.close), i)?;
+            Some(OhlcPoint {
+                timestamp,
+                open: value_at(quote.map(|q| &q.open), i).unwrap_or(close),
+                high: value_at(quote.map(|q| &q.high), i).unwrap_or(close),
+                low: value_at(quote.map(|q| &q.low), i).unwrap_or(close),
+                close,
+                volume: quote.and_then(|q| q.volume.get(i).copied().flatten()),
+            })
+        })
+        .collect();
+
+    let meta = result.meta;
+
+    Ok(StockChart {
+        ticker: ticker.to_string(),
+        range,
+        interval: interval.to_string(),
+        currency_code: meta.currency.as_deref().unwrap_or("USD").to_uppercase(),
+        price_hint: meta.price_hint,
+        previous_close: (meta.chart_previous_close > 0.0).then_some(meta.chart_previous_close),
+        points,
+        last_updated: Local::now().to_rfc3339(),
+    })
+}
+
+fn asset_kind_for(quote_type: &str) -> AssetKind {
+    match quote_type {
+        "CRYPTOCURRENCY" => AssetKind::Crypto,
+        "CURRENCY" => AssetKind::Fx,
+        "INDEX" => AssetKind::Index,
+        "FUTURE" => AssetKind::Commodity,
+        _ => AssetKind::Equity,
+    }
+}
+
+/// Symbols matching a free-text query, with currencies looked up from each chart
+pub(super) async fn search(client: &Client, query: &str) -> Result<Vec<SymbolMatch>, String> {
+    let url = format!(
+        "{}?q={}&quotesCount={}&newsCount=0",
+        YAHOO_SEARCH_URL,
+        urlencoding::encode(query),
+        MAX_SEARCH_RESULTS
+    );
+
+    let response = client
+        .get(&url)
+        .header("User-Agent", USER_AGENT)
+        .send()
+        .await
+        .map_err(|e| format!("Symbol search request failed: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Symbol search error: {}", response.status()));
+    }
+
+    let data: SearchResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse symbol search response: {}", e))?;
+
+    let quotes: Vec<SearchQuote> = data
+        .quotes
+        .into_iter()
+        .filter(|q| q.is_yahoo_finance)
+        .take(MAX_SEARCH_RESULTS)
+        .collect();
+
+    // Search results don't include a currency, so ask the chart API for each
+    let mut lookups = JoinSet::new();
+    for (index, quote) in quotes.iter().enumerate() {
+        let client = client.clone();
+        let symbol = quote.symbol.clone();
+        lookups.spawn(async move {
+            let result = tokio::time::timeout(
+                SEARCH_LOOKUP_TIMEOUT,
+                fetch_chart_result(&client, &symbol, "1d", "1d", false),
+            )
+            .await;
+            let currency = match result {
+                Ok(Ok(chart)) => chart.meta.currency.map(|c| c.to_uppercase()),
+                _ => None,
+            };
+            (index, currency)
+        });
+    }
+
+    let mut currencies = vec![None; quotes.len()];
+    while let Some(Ok((index, currency))) = lookups.join_next().await {
+        currencies[index] = currency;
+    }
+
+    Ok(quotes
+        .into_iter()
+        .zip(currencies)
+        .map(|(quote, currency_code)| {
+            let quote_type = quote.quote_type.unwrap_or_default();
+            SymbolMatch {
+                name: quote
+                    .long_name
+                    .or(quote.short_name)
+                    .unwrap_or_else(|| quote.symbol.clone()),
+                symbol: quote.symbol,
+                exchange: quote
+                    .exchange_display
+                    .or(quote.exchange)
+                    .unwrap_or_default(),
+                asset_kind: asset_kind_for(&quote_type),
+                type_display: quote.type_disp.unwrap_or(quote_type),
+                currency_code,
+            }
+        })
+        .collect())
+}
+
+impl MarketDataProvider for Yahoo {
+    async fn fetch_quote(
+        &self,
+        client: &Client,
+        _config: &StocksConfig,
+        ticker: &str,
+    ) -> Result<StockData, String> {
+        fetch_quote(client, ticker).await
+    }
+
+    async fn fetch_chart(
+        &self,
+        client: &Client,
+        _config: &StocksConfig,
+        ticker: &str,
+        range: ChartRange,
+    ) -> Result<StockChart, String> {
+        fetch_chart(client, ticker, range).await
+    }
+}
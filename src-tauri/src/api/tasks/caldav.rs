@@ -0,0 +1,574 @@
+use super::TaskProvider;
+use crate::api::{Task, TaskList, TasksData};
+use crate::config::{CalDavConfig, TaskProviderKind, TasksConfig};
+use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use reqwest::header::{CONTENT_TYPE, ETAG, IF_MATCH};
+use reqwest::{Client, Method, RequestBuilder, StatusCode};
+use url::Url;
+
+/// Every VTODO in the collection with its etag
+const CALENDAR_QUERY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<c:calendar-query xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+  <d:prop>
+    <d:getetag/>
+    <c:calendar-data/>
+  </d:prop>
+  <c:filter>
+    <c:comp-filter name="VCALENDAR">
+      <c:comp-filter name="VTODO"/>
+    </c:comp-filter>
+  </c:filter>
+</c:calendar-query>"#;
+
+/// VTODOs in a single CalDAV collection. Task ids are the hrefs of their
+/// calendar objects, so completing one can write it back in place
+pub struct CalDav;
+
+fn collection_url(config: &CalDavConfig) -> Result<Url, String> {
+    if config.url.is_empty() {
+        return Err("CalDAV url is not configured".to_string());
+    }
+    Url::parse(&config.url).map_err(|e| format!("Invalid CalDAV url: {}", e))
+}
+
+fn authorize(request: RequestBuilder, config: &CalDavConfig) -> RequestBuilder {
+    if config.username.is_empty() {
+        request
+    } else {
+        request.basic_auth(&config.username, Some(&config.password))
+    }
+}
+
+/// Contents of every element with this local name, whatever its namespace prefix
+fn elements<'a>(xml: &'a str, name: &str) -> Vec<&'a str> {
+    let mut found = Vec::new();
+    let mut rest = xml;
+
+    while let Some(open) = rest.find('<') {
+        rest = &rest[open + 1..];
+        let tag_end = rest
+            .find(|c: char| c.is_whitespace() || c == '>' || c == '/')
+            .unwrap_or(rest.len());
+        let tag = &rest[..tag_end];
+        // Closing tags have an empty name here, since they start with '/'
+        if tag.is_empty() || tag.rsplit(':').next() != Some(name) {
+            continue;
+        }
+
+        let Some(close) = rest.find('>') else { break };
+        if rest[..close].ends_with('/') {
+            found.push("");
+            rest = &rest[close + 1..];
+            continue;
+        }
+
+        let body = &rest[close + 1..];
+        let end_tag = format!("</{}>", tag);
+        let Some(end) = body.find(&end_tag) else {
+            break;
+        };
+        found.push(&body[..end]);
+        rest = &body[end + end_tag.len()..];
+    }
+
+    found
+}
+
+fn xml_text(text: &str) -> String {
+    let text = text.trim();
+    if let Some(cdata) = text
+        .strip_prefix("<![CDATA[")
+        .and_then(|t| t.strip_suffix("]]>"))
+    {
+        return cdata.to_string();
+    }
+
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&#13;", "\r")
+        .replace("&#xD;", "\r")
+        .replace("&amp;", "&")
+}
+
+/// Content lines with folded continuations joined back up
+fn unfold(ical: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in ical.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+/// Name, parameters and value of a line such as `DUE;VALUE=DATE:20240131`
+fn property(line: &str) -> Option<(&str, &str, &str)> {
+    let (head, value) = line.split_once(':')?;
+    let (name, params) = head.split_once(';').unwrap_or((head, ""));
+    Some((name, params, value))
+}
+
+fn param<'a>(params: &'a str, key: &str) -> Option<&'a str> {
+    params
+        .split(';')
+        .filter_map(|p| p.split_once('='))
+        .find(|(k, _)| k.eq_ignore_ascii_case(key))
+        .map(|(_, v)| v.trim_matches('"'))
+}
+
+fn unescape_text(value: &str) -> String {
+    let mut text = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => text.push('\n'),
+            Some(other) => text.push(other),
+            None => {}
+        }
+    }
+    text
+}
+
+/// An iCalendar DATE or DATE-TIME as RFC 3339, or YYYY-MM-DD for a date
+fn ical_time(params: &str, value: &str) -> Option<(String, bool)> {
+    if param(params, "VALUE") == Some("DATE") || value.len() == 8 {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+        return Some((date.format("%Y-%m-%d").to_string(), true));
+    }
+
+    let (naive, utc) = match value.strip_suffix('Z') {
+        Some(value) => (value, true),
+        None => (value, false),
+    };
+    let naive = NaiveDateTime::parse_from_str(naive, "%Y%m%dT%H%M%S").ok()?;
+
+    let time = if utc {
+        Utc.from_utc_datetime(&naive).with_timezone(&Local)
+    } else if let Some(tz) = param(params, "TZID").and_then(|tz| tz.parse::<Tz>().ok()) {
+        tz.from_local_datetime(&naive)
+            .earliest()?
+            .with_timezone(&Local)
+    } else {
+        // Floating time, the same wall clock time everywhere
+        Local.from_local_datetime(&naive).earliest()?
+    };
+
+    Some((time.to_rfc3339(), false))
+}
+
+/// iCalendar 1 (highest) to 9 (lowest), on TickTick's 5/3/1/0 scale
+fn priority(value: &str) -> i32 {
+    match value.trim().parse::<i32>().unwrap_or(0) {
+        1..=4 => 5,
+        5 => 3,
+        6..=9 => 1,
+        _ => 0,
+    }
+}
+
+/// The first VTODO in a calendar object, if it's still open
+fn parse_todo(href: &str, ical: &str, list: &TaskList) -> Option<Task> {
+    let lines = unfold(ical);
+    let start = lines.iter().position(|l| l == "BEGIN:VTODO")?;
+
+    let mut task = Task {
+        id: href.to_string(),
+        list_id: list.id.clone(),
+        list_name: Some(list.name.clone()),
+        title: String::new(),
+        notes: None,
+        due_date: None,
+        is_all_day: false,
+        priority: 0,
+        tags: Vec::new(),
+        is_completed: false,
+        modified_time: None,
+    };
+    // Nested components such as VALARM have properties of their own
+    let mut depth = 0;
+
+    for line in &lines[start + 1..] {
+        if line == "END:VTODO" && depth == 0 {
+            break;
+        }
+        if line.starts_with("BEGIN:") {
+            depth += 1;
+            continue;
+        }
+        if line.starts_with("END:") {
+            depth -= 1;
+            continue;
+        }
+        if depth > 0 {
+            continue;
+        }
+
+        let Some((name, params, value)) = property(line) else {
+            continue;
+        };
+        match name.to_ascii_uppercase().as_str() {
+            "SUMMARY" => task.title = unescape_text(value),
+            "DESCRIPTION" => {
+                task.notes = Some(unescape_text(value)).filter(|n| !n.trim().is_empty())
+            }
+            "DUE" => {
+                if let Some((due, all_day)) = ical_time(params, value) {
+                    task.due_date = Some(due);
+                    task.is_all_day = all_day;
+                }
+            }
+            "PRIORITY" => task.priority = priority(value),
+            "CATEGORIES" => task.tags.extend(
+                value
+                    .split(',')
+                    .map(|t| unescape_text(t.trim()))
+                    .filter(|t| !t.is_empty()),
+            ),
+            "STATUS" => {
+                task.is_completed |= matches!(value, "COMPLETED" | "CANCELLED");
+            }
+            "COMPLETED" => task.is_completed = true,
+            "LAST-MODIFIED" => task.modified_time = ical_time(params, value).map(|(t, _)| t),
+            _ => {}
+        }
+    }
+
+    (!task.is_completed).then_some(task)
+}
+
+/// The calendar object with its first VTODO marked completed
+fn mark_completed(ical: &str) -> Result<String, String> {
+    let now = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let mut output = Vec::new();
+    let mut in_todo = false;
+    let mut done = false;
+    let mut depth = 0;
+
+    for line in unfold(ical) {
+        if !done && line == "BEGIN:VTODO" {
+            in_todo = true;
+        } else if in_todo && depth == 0 && line == "END:VTODO" {
+            output.push("STATUS:COMPLETED".to_string());
+            output.push(format!("COMPLETED:{}", now));
+            output.push("PERCENT-COMPLETE:100".to_string());
+            output.push(format!("LAST-MODIFIED:{}", now));
+            in_todo = false;
+            done = true;
+        } else if in_todo && line.starts_with("BEGIN:") {
+            depth += 1;
+        } else if in_todo && line.starts_with("END:") {
+            depth -= 1;
+        } else if in_todo && depth == 0 {
+            let name = property(&line).map(|(name, _, _)| name.to_ascii_uppercase());
+            if matches!(
+                name.as_deref(),
+                Some("STATUS" | "COMPLETED" | "PERCENT-COMPLETE" | "LAST-MODIFIED")
+            ) {
+                continue;
+            }
+        }
+        output.push(line);
+    }
+
+    if !done {
+        return Err("CalDAV object has no VTODO".to_string());
+    }
+    Ok(output.join("\r\n") + "\r\n")
+}
+
+fn task_list(config: &CalDavConfig) -> TaskList {
+    TaskList {
+        id: config.url.clone(),
+        name: config.name.clone().unwrap_or_else(|| "Tasks".to_string()),
+        color: None,
+    }
+}
+
+impl TaskProvider for CalDav {
+    async fn fetch_tasks(
+        &self,
+        client: &Client,
+        config: &TasksConfig,
+    ) -> Result<TasksData, String> {
+        let caldav = &config.caldav;
+        let url = collection_url(caldav)?;
+        let report = Method::from_bytes(b"REPORT").map_err(|e| e.to_string())?;
+
+        let response = authorize(client.request(report, url), caldav)
+            .header("Depth", "1")
+            .header(CONTENT_TYPE, "application/xml; charset=utf-8")
+            .body(CALENDAR_QUERY)
+            .send()
+            .await
+            .map_err(|e| format!("CalDAV request failed: {}", e))?;
+
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        if !status.is_success() {
+            log::error!("CalDAV error {}: {}", status, body);
+            return Err(format!("CalDAV error: {} - {}", status, body));
+        }
+
+        let list = task_list(caldav);
+        let tasks = elements(&body, "response")
+            .into_iter()
+            .filter_map(|response| {
+                let href = xml_text(elements(response, "href").first()?);
+                let ical = xml_text(elements(response, "calendar-data").first()?);
+                parse_todo(&href, &ical, &list)
+            })
+            .collect();
+
+        Ok(TasksData {
+            provider: TaskProviderKind::CalDav,
+            tasks,
+            lists: vec![list],
+            last_updated: Local::now().to_rfc3339(),
+        })
+    }
+
+    async fn complete_task(
+        &self,
+        client: &Client,
+        config: &TasksConfig,
+        _list_id: &str,
+        task_id: &str,
+    ) -> Result<(), String> {
+        let caldav = &config.caldav;
+        let url = collection_url(caldav)?
+            .join(task_id)
+            .map_err(|e| format!("Invalid CalDAV task href '{}': {}", task_id, e))?;
+
+        let response = authorize(client.get(url.clone()), caldav)
+            .send()
+            .await
+            .map_err(|e| format!("CalDAV request failed: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("CalDAV error: {}", response.status()));
+        }
+        let etag = response.headers().get(ETAG).cloned();
+        let ical = response
+            .text()
+            .await
+            .map_err(|e| format!("Failed to read CalDAV task: {}", e))?;
+
+        let mut request = authorize(client.put(url), caldav)
+            .header(CONTENT_TYPE, "text/calendar; charset=utf-8")
+            .body(mark_completed(&ical)?);
+        // Only overwrite the version we read
+        if let Some(etag) = etag {
+            request = request.header(IF_MATCH, etag);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("CalDAV request failed: {}", e))?;
+        match response.status() {
+            StatusCode::PRECONDITION_FAILED => {
+                Err("Task changed on the CalDAV server, refresh and try again".to_string())
+            }
+            status if status.is_success() => Ok(()),
+            status => {
+                let body = response.text().await.unwrap_or_default();
+                Err(format!("CalDAV error: {} - {}", status, body))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+
+    const MULTISTATUS: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<D:multistatus xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+  <D:response>
+    <D:href>/dav/tasks/a%20b.ics</D:href>
+    <D:propstat>
+      <D:prop>
+        <D:getetag>"1"</D:getetag>
+        <C:calendar-data><![CDATA[BEGIN:VCALENDAR
+BEGIN:VTODO
+UID:a
+SUMMARY:Buy milk & eggs
+END:VTODO
+END:VCALENDAR
+]]></C:calendar-data>
+      </D:prop>
+    </D:propstat>
+  </D:response>
+  <D:response>
+    <D:href>/dav/tasks/b.ics</D:href>
+    <D:propstat>
+      <D:prop>
+        <D:getetag/>
+        <cal:calendar-data xmlns:cal="urn:ietf:params:xml:ns:caldav">BEGIN:VCALENDAR&#13;
+BEGIN:VTODO&#13;
+SUMMARY:Call &lt;Sam&gt;&#13;
+END:VTODO&#13;
+END:VCALENDAR&#13;
+</cal:calendar-data>
+      </D:prop>
+    </D:propstat>
+  </D:response>
+</D:multistatus>"#;
+
+    const TODO: &str = "BEGIN:VCALENDAR\r\n\
+VERSION:2.0\r\n\
+BEGIN:VTODO\r\n\
+UID:1234\r\n\
+SUMMARY:Renew the passport before the\r\n\x20 trip\r\n\
+DESCRIPTION:Photos\\, form\\nand fee\r\n\
+DUE;TZID=America/Toronto:20240131T090000\r\n\
+PRIORITY:1\r\n\
+CATEGORIES:errands,travel\r\n\
+STATUS:NEEDS-ACTION\r\n\
+LAST-MODIFIED:20240101T120000Z\r\n\
+BEGIN:VALARM\r\n\
+ACTION:DISPLAY\r\n\
+DESCRIPTION:Reminder\r\n\
+TRIGGER:-PT30M\r\n\
+STATUS:COMPLETED\r\n\
+END:VALARM\r\n\
+END:VTODO\r\n\
+END:VCALENDAR\r\n";
+
+    fn list() -> TaskList {
+        TaskList {
+            id: "https://dav.example.com/tasks/".to_string(),
+            name: "Tasks".to_string(),
+            color: None,
+        }
+    }
+
+    fn utc(time: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(time)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn reads_multistatus_with_any_prefix() {
+        let responses = elements(MULTISTATUS, "response");
+        assert_eq!(responses.len(), 2);
+
+        let hrefs: Vec<String> = responses
+            .iter()
+            .map(|r| xml_text(elements(r, "href")[0]))
+            .collect();
+        assert_eq!(hrefs, ["/dav/tasks/a%20b.ics", "/dav/tasks/b.ics"]);
+        assert_eq!(elements(responses[1], "getetag"), [""]);
+
+        let titles: Vec<String> = responses
+            .iter()
+            .filter_map(|r| {
+                let ical = xml_text(elements(r, "calendar-data").first()?);
+                parse_todo("href", &ical, &list()).map(|t| t.title)
+            })
+            .collect();
+        assert_eq!(titles, ["Buy milk & eggs", "Call <Sam>"]);
+    }
+
+    #[test]
+    fn parses_a_todo_with_folded_lines_and_an_alarm() {
+        let task = parse_todo("/dav/tasks/1234.ics", TODO, &list()).unwrap();
+
+        assert_eq!(task.id, "/dav/tasks/1234.ics");
+        assert_eq!(task.title, "Renew the passport before the trip");
+        assert_eq!(task.notes.as_deref(), Some("Photos, form\nand fee"));
+        assert_eq!(task.priority, 5);
+        assert_eq!(task.tags, ["errands", "travel"]);
+        // The alarm's DESCRIPTION and STATUS belong to the alarm, not the task
+        assert!(!task.is_completed);
+        assert!(!task.is_all_day);
+        assert_eq!(
+            utc(task.due_date.as_deref().unwrap()),
+            utc("2024-01-31T14:00:00Z")
+        );
+        assert_eq!(
+            utc(task.modified_time.as_deref().unwrap()),
+            utc("2024-01-01T12:00:00Z")
+        );
+    }
+
+    #[test]
+    fn reads_due_dates_and_times() {
+        assert_eq!(
+            ical_time("VALUE=DATE", "20240131"),
+            Some(("2024-01-31".to_string(), true))
+        );
+        assert_eq!(
+            ical_time("", "20240131"),
+            Some(("2024-01-31".to_string(), true))
+        );
+
+        let (time, all_day) = ical_time("TZID=\"Europe/Berlin\"", "20240615T083000").unwrap();
+        assert!(!all_day);
+        assert_eq!(utc(&time), utc("2024-06-15T06:30:00Z"));
+
+        let (time, _) = ical_time("", "20240615T083000Z").unwrap();
+        assert_eq!(utc(&time), utc("2024-06-15T08:30:00Z"));
+
+        assert_eq!(ical_time("VALUE=DATE", "2024-01-31"), None);
+    }
+
+    #[test]
+    fn skips_completed_todos() {
+        let done = TODO.replace("STATUS:NEEDS-ACTION", "STATUS:COMPLETED");
+        assert!(parse_todo("href", &done, &list()).is_none());
+
+        let cancelled = TODO.replace("STATUS:NEEDS-ACTION", "STATUS:CANCELLED");
+        assert!(parse_todo("href", &cancelled, &list()).is_none());
+    }
+
+    #[test]
+    fn marks_the_todo_completed_keeping_nested_components() {
+        let completed = mark_completed(TODO).unwrap();
+        let lines: Vec<&str> = completed.split("\r\n").collect();
+
+        let alarm: Vec<&str> = lines
+            .iter()
+            .skip_while(|l| **l != "BEGIN:VALARM")
+            .take_while(|l| **l != "END:VALARM")
+            .copied()
+            .collect();
+        assert_eq!(
+            alarm,
+            [
+                "BEGIN:VALARM",
+                "ACTION:DISPLAY",
+                "DESCRIPTION:Reminder",
+                "TRIGGER:-PT30M",
+                "STATUS:COMPLETED",
+            ]
+        );
+
+        let todo_end = lines.iter().position(|l| *l == "END:VTODO").unwrap();
+        assert!(lines[todo_end - 4..todo_end]
+            .iter()
+            .zip([
+                "STATUS:COMPLETED",
+                "COMPLETED:",
+                "PERCENT-COMPLETE:100",
+                "LAST-MODIFIED:",
+            ])
+            .all(|(line, prefix)| line.starts_with(prefix)));
+        assert!(!completed.contains("STATUS:NEEDS-ACTION"));
+        assert!(!completed.contains("LAST-MODIFIED:20240101T120000Z"));
+        assert!(completed.contains("SUMMARY:Renew the passport before the trip\r\n"));
+        assert!(completed.ends_with("END:VCALENDAR\r\n"));
+
+        assert!(parse_todo("href", &completed, &list()).is_none());
+        assert!(mark_completed("BEGIN:VCALENDAR\r\nEND:VCALENDAR\r\n").is_err());
+    }
+}
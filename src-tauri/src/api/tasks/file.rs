@@ -0,0 +1,430 @@
+use super::TaskProvider;
+use crate::api::{Task, TaskList, TasksData};
+use crate::config::{get_config_dir, TaskProviderKind, TasksConfig};
+use chrono::{Local, NaiveDate};
+use reqwest::Client;
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+/// List for tasks outside any heading (Markdown) or project (todo.txt)
+const DEFAULT_LIST: &str = "Tasks";
+
+/// A plain task file in the config dir, either a Markdown checklist
+/// (`- [ ] Call mum due:2024-01-31 #family !high`, grouped by headings) or
+/// todo.txt (`(A) Call mum due:2024-01-31 +Family @phone`)
+pub struct TaskFile;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Markdown,
+    TodoTxt,
+}
+
+fn file_path(config: &TasksConfig) -> Result<PathBuf, String> {
+    match &config.file_path {
+        Some(path) => Ok(PathBuf::from(path)),
+        None => get_config_dir()
+            .map(|p| p.join("tasks.md"))
+            .map_err(|e| e.to_string()),
+    }
+}
+
+fn format_of(path: &Path) -> Format {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("txt") => Format::TodoTxt,
+        _ => Format::Markdown,
+    }
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read task file {}: {}", path.display(), e))
+}
+
+/// Line number plus a hash of the line, so a task is only completed if its
+/// line hasn't been edited since it was read
+fn line_id(index: usize, line: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    line.trim_end().hash(&mut hasher);
+    format!("{}:{:x}", index + 1, hasher.finish())
+}
+
+/// Whether the line is done and the text after the checkbox, for `- [ ] ...`
+fn markdown_item(line: &str) -> Option<(bool, &str)> {
+    let item = line
+        .trim_start()
+        .strip_prefix(['-', '*', '+'])?
+        .strip_prefix(' ')?;
+
+    if let Some(rest) = item.strip_prefix("[ ]") {
+        return Some((false, rest));
+    }
+    item.strip_prefix("[x]")
+        .or_else(|| item.strip_prefix("[X]"))
+        .map(|rest| (true, rest))
+}
+
+/// Whether the line is done, its `(A)`-style priority and the remaining text
+fn todo_txt_item(line: &str) -> Option<(bool, i32, &str)> {
+    let line = line.trim();
+    if line.is_empty() {
+        return None;
+    }
+
+    let (done, mut rest) = match line.strip_prefix("x ") {
+        Some(rest) => (true, rest),
+        None => (false, line),
+    };
+
+    let mut priority = 0;
+    let bytes = rest.as_bytes();
+    if bytes.len() >= 4
+        && bytes[0] == b'('
+        && bytes[1].is_ascii_uppercase()
+        && bytes[2] == b')'
+        && bytes[3] == b' '
+    {
+        priority = match bytes[1] {
+            b'A' => 5,
+            b'B' => 3,
+            _ => 1,
+        };
+        rest = &rest[4..];
+    }
+
+    // Completion and creation dates come before the description
+    while let Some((word, remaining)) = rest.split_once(' ') {
+        if NaiveDate::parse_from_str(word, "%Y-%m-%d").is_err() {
+            break;
+        }
+        rest = remaining;
+    }
+
+    Some((done, priority, rest))
+}
+
+/// Title, list, tags, priority and due date from a task's text
+fn parse_words(text: &str, format: Format, task: &mut Task) {
+    let mut title = Vec::new();
+
+    for word in text.split_whitespace() {
+        if let Some(date) = word.strip_prefix("due:") {
+            if NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok() {
+                task.due_date = Some(date.to_string());
+                task.is_all_day = true;
+                continue;
+            }
+        }
+
+        match format {
+            Format::TodoTxt if word.len() > 1 && word.starts_with('+') => {
+                task.list_id = word[1..].to_string();
+                task.list_name = Some(word[1..].to_string());
+            }
+            Format::TodoTxt if word.len() > 1 && word.starts_with('@') => {
+                task.tags.push(word[1..].to_string())
+            }
+            Format::Markdown if word.len() > 1 && word.starts_with('#') => {
+                task.tags.push(word[1..].to_string())
+            }
+            Format::Markdown if matches!(word, "!high" | "!medium" | "!low") => {
+                task.priority = match word {
+                    "!high" => 5,
+                    "!medium" => 3,
+                    _ => 1,
+                };
+            }
+            _ => title.push(word),
+        }
+    }
+
+    task.title = title.join(" ");
+}
+
+fn parse_tasks(content: &str, format: Format) -> (Vec<TaskList>, Vec<Task>) {
+    let mut tasks = Vec::new();
+    let mut list = DEFAULT_LIST.to_string();
+
+    for (index, line) in content.lines().enumerate() {
+        if format == Format::Markdown {
+            if let Some(heading) = line.strip_prefix('#') {
+                list = heading.trim_start_matches('#').trim().to_string();
+                continue;
+            }
+        }
+
+        let (done, priority, text) = match format {
+            Format::Markdown => match markdown_item(line) {
+                Some((done, text)) => (done, 0, text),
+                None => continue,
+            },
+            Format::TodoTxt => match todo_txt_item(line) {
+                Some(item) => item,
+                None => continue,
+            },
+        };
+        if done {
+            continue;
+        }
+
+        let mut task = Task {
+            id: line_id(index, line),
+            list_id: list.clone(),
+            list_name: Some(list.clone()),
+            title: String::new(),
+            notes: None,
+            due_date: None,
+            is_all_day: false,
+            priority,
+            tags: Vec::new(),
+            is_completed: false,
+            modified_time: None,
+        };
+        parse_words(text, format, &mut task);
+        tasks.push(task);
+    }
+
+    let mut lists: Vec<TaskList> = Vec::new();
+    for task in &tasks {
+        if !lists.iter().any(|l| l.id == task.list_id) {
+            lists.push(TaskList {
+                id: task.list_id.clone(),
+                name: task.list_id.clone(),
+                color: None,
+            });
+        }
+    }
+
+    (lists, tasks)
+}
+
+/// The line ticked off: `[x]` in Markdown, `x <date>` in todo.txt
+fn complete_line(line: &str, format: Format) -> String {
+    match format {
+        Format::Markdown => line.replacen("[ ]", "[x]", 1),
+        Format::TodoTxt => {
+            let line = line.trim_start();
+            // Priorities are dropped from completed todo.txt tasks
+            let line = match line.as_bytes() {
+                [b'(', p, b')', b' ', ..] if p.is_ascii_uppercase() => &line[4..],
+                _ => line,
+            };
+            format!("x {} {}", Local::now().format("%Y-%m-%d"), line)
+        }
+    }
+}
+
+impl TaskProvider for TaskFile {
+    async fn fetch_tasks(
+        &self,
+        _client: &Client,
+        config: &TasksConfig,
+    ) -> Result<TasksData, String> {
+        let path = file_path(config)?;
+        let (lists, tasks) = parse_tasks(&read(&path)?, format_of(&path));
+
+        Ok(TasksData {
+            provider: TaskProviderKind::File,
+            tasks,
+            lists,
+            last_updated: Local::now().to_rfc3339(),
+        })
+    }
+
+    async fn complete_task(
+        &self,
+        _client: &Client,
+        config: &TasksConfig,
+        _list_id: &str,
+        task_id: &str,
+    ) -> Result<(), String> {
+        let path = file_path(config)?;
+        let format = format_of(&path);
+        let content = read(&path)?;
+
+        let index = task_id
+            .split_once(':')
+            .and_then(|(line, _)| line.parse::<usize>().ok())
+            .and_then(|line| line.checked_sub(1))
+            .ok_or_else(|| format!("Invalid task id '{}'", task_id))?;
+
+        let mut lines: Vec<String> = content.split_inclusive('\n').map(String::from).collect();
+        let line = lines
+            .get_mut(index)
+            .filter(|line| line_id(index, line) == task_id)
+            .ok_or("Task file changed, refresh and try again")?;
+
+        let ending = &line[line.trim_end_matches(['\r', '\n']).len()..];
+        let completed = complete_line(line.trim_end_matches(['\r', '\n']), format) + ending;
+        *line = completed;
+
+        fs::write(&path, lines.concat())
+            .map_err(|e| format!("Failed to write task file {}: {}", path.display(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A task file in the temp dir, removed when dropped
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str, content: &str) -> Self {
+            let path =
+                std::env::temp_dir().join(format!("inkdash-{}-{}", std::process::id(), name));
+            fs::write(&path, content).unwrap();
+            TempFile(path)
+        }
+
+        fn config(&self) -> TasksConfig {
+            TasksConfig {
+                file_path: Some(self.0.to_string_lossy().into_owned()),
+                ..Default::default()
+            }
+        }
+
+        fn content(&self) -> String {
+            fs::read_to_string(&self.0).unwrap()
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    fn today() -> String {
+        Local::now().format("%Y-%m-%d").to_string()
+    }
+
+    async fn fetch(file: &TempFile) -> TasksData {
+        TaskFile
+            .fetch_tasks(&Client::new(), &file.config())
+            .await
+            .unwrap()
+    }
+
+    async fn complete(file: &TempFile, task: &Task) -> Result<(), String> {
+        TaskFile
+            .complete_task(&Client::new(), &file.config(), &task.list_id, &task.id)
+            .await
+    }
+
+    #[test]
+    fn parses_markdown_items() {
+        let content = "- [ ] Loose end\n\
+# Home\n\
+- [ ] Call mum due:2024-01-31 #family !high\n\
+- [x] Water plants\n\
+Some notes\n\
+## Work\n\
+* [ ] Send report !low\n";
+        let (lists, tasks) = parse_tasks(content, Format::Markdown);
+
+        let names: Vec<&str> = lists.iter().map(|l| l.name.as_str()).collect();
+        assert_eq!(names, ["Tasks", "Home", "Work"]);
+
+        let titles: Vec<&str> = tasks.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, ["Loose end", "Call mum", "Send report"]);
+
+        let call = &tasks[1];
+        assert_eq!(call.list_id, "Home");
+        assert_eq!(call.due_date.as_deref(), Some("2024-01-31"));
+        assert!(call.is_all_day);
+        assert_eq!(call.tags, ["family"]);
+        assert_eq!(call.priority, 5);
+        assert_eq!(tasks[2].priority, 1);
+    }
+
+    #[test]
+    fn parses_todo_txt_items() {
+        let content = "(A) 2024-01-01 Call mum due:2024-01-31 +Family @phone\n\
+x 2024-01-02 2024-01-01 Water plants\n\
+\n\
+(C) Tidy desk\n";
+        let (lists, tasks) = parse_tasks(content, Format::TodoTxt);
+
+        assert_eq!(tasks.len(), 2);
+        let call = &tasks[0];
+        assert_eq!(call.title, "Call mum");
+        assert_eq!(call.priority, 5);
+        assert_eq!(call.list_name.as_deref(), Some("Family"));
+        assert_eq!(call.tags, ["phone"]);
+        assert_eq!(call.due_date.as_deref(), Some("2024-01-31"));
+        assert_eq!(tasks[1].priority, 1);
+        assert_eq!(tasks[1].list_id, DEFAULT_LIST);
+
+        let names: Vec<&str> = lists.iter().map(|l| l.name.as_str()).collect();
+        assert_eq!(names, ["Family", "Tasks"]);
+    }
+
+    #[tokio::test]
+    async fn completes_markdown_tasks_keeping_crlf() {
+        let file = TempFile::new(
+            "tasks.md",
+            "# Home\r\n- [ ] Call mum\r\n- [ ] Water plants\r\n",
+        );
+
+        let data = fetch(&file).await;
+        assert_eq!(data.tasks[0].title, "Call mum");
+        complete(&file, &data.tasks[0]).await.unwrap();
+
+        assert_eq!(
+            file.content(),
+            "# Home\r\n- [x] Call mum\r\n- [ ] Water plants\r\n"
+        );
+        let titles: Vec<String> = fetch(&file)
+            .await
+            .tasks
+            .into_iter()
+            .map(|t| t.title)
+            .collect();
+        assert_eq!(titles, ["Water plants"]);
+    }
+
+    #[tokio::test]
+    async fn completes_todo_txt_tasks() {
+        let file = TempFile::new("todo.txt", "(A) 2024-01-01 Call mum +Family\r\nTidy desk");
+
+        let data = fetch(&file).await;
+        complete(&file, &data.tasks[0]).await.unwrap();
+        complete(&file, &data.tasks[1]).await.unwrap();
+
+        assert_eq!(
+            file.content(),
+            format!(
+                "x {today} 2024-01-01 Call mum +Family\r\nx {today} Tidy desk",
+                today = today()
+            )
+        );
+        assert!(fetch(&file).await.tasks.is_empty());
+    }
+
+    #[tokio::test]
+    async fn rejects_stale_line_ids() {
+        let file = TempFile::new("stale.md", "- [ ] Call mum\n- [ ] Water plants\n");
+        let data = fetch(&file).await;
+
+        fs::write(&file.0, "- [ ] Call dad\n- [ ] Water plants\n").unwrap();
+        assert_eq!(
+            complete(&file, &data.tasks[0]).await,
+            Err("Task file changed, refresh and try again".to_string())
+        );
+        assert_eq!(file.content(), "- [ ] Call dad\n- [ ] Water plants\n");
+
+        // Lines that moved are rejected too, since the id includes the line number
+        fs::write(&file.0, "# Chores\n- [ ] Call mum\n- [ ] Water plants\n").unwrap();
+        assert!(complete(&file, &data.tasks[0]).await.is_err());
+
+        let bogus = Task {
+            id: "not-a-line".to_string(),
+            ..data.tasks[1].clone()
+        };
+        assert!(complete(&file, &bogus).await.is_err());
+    }
+}
@@ -0,0 +1,64 @@
+mod caldav;
+mod file;
+mod ticktick;
+mod todoist;
+
+use super::TasksData;
+use crate::config::{AppConfig, TaskProviderKind, TasksConfig};
+use reqwest::Client;
+
+pub use caldav::CalDav;
+pub use file::TaskFile;
+pub use ticktick::TickTick;
+pub use todoist::Todoist;
+
+/// A source of tasks, mapped into the shared `Task` and `TaskList`
+pub trait TaskProvider {
+    /// Open tasks and the lists they belong to
+    async fn fetch_tasks(&self, client: &Client, config: &TasksConfig)
+        -> Result<TasksData, String>;
+
+    async fn complete_task(
+        &self,
+        client: &Client,
+        config: &TasksConfig,
+        list_id: &str,
+        task_id: &str,
+    ) -> Result<(), String>;
+}
+
+pub async fn fetch_tasks(config: &AppConfig) -> Result<TasksData, String> {
+    let client = Client::new();
+    let tasks = &config.tasks;
+
+    match tasks.provider {
+        TaskProviderKind::TickTick => TickTick(&config.ticktick).fetch_tasks(&client, tasks).await,
+        TaskProviderKind::CalDav => CalDav.fetch_tasks(&client, tasks).await,
+        TaskProviderKind::Todoist => Todoist.fetch_tasks(&client, tasks).await,
+        TaskProviderKind::File => TaskFile.fetch_tasks(&client, tasks).await,
+    }
+}
+
+pub async fn complete_task(config: &AppConfig, list_id: &str, task_id: &str) -> Result<(), String> {
+    let client = Client::new();
+    let tasks = &config.tasks;
+
+    match tasks.provider {
+        TaskProviderKind::TickTick => {
+            TickTick(&config.ticktick)
+                .complete_task(&client, tasks, list_id, task_id)
+                .await
+        }
+        TaskProviderKind::CalDav => CalDav.complete_task(&client, tasks, list_id, task_id).await,
+        TaskProviderKind::Todoist => {
+            Todoist
+                .complete_task(&client, tasks, list_id, task_id)
+                .await
+        }
+        TaskProviderKind::File => {
+            TaskFile
+                .complete_task(&client, tasks, list_id, task_id)
+                .await
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use super::TaskProvider;
+use crate::api::ticktick::{self, parse_ticktick_date};
+use crate::api::{Task, TaskList, TasksData, TickTickTask};
+use crate::config::{TaskProviderKind, TasksConfig, TickTickConfig};
+use reqwest::Client;
+
+/// The existing TickTick sync, including its local store and throttling
+pub struct TickTick<'a>(pub &'a TickTickConfig);
+
+fn to_task(task: TickTickTask) -> Task {
+    let due_date = match &task.due_day {
+        Some(day) => Some(day.clone()),
+        None => task
+            .due_date
+            .as_deref()
+            .and_then(parse_ticktick_date)
+            .map(|d| d.to_rfc3339()),
+    };
+
+    Task {
+        id: task.id,
+        list_id: task.project_id,
+        list_name: task.project_name,
+        title: task.title,
+        notes: task.notes,
+        is_all_day: task.due_day.is_some(),
+        due_date,
+        priority: task.priority,
+        tags: task.tags,
+        is_completed: task.is_completed,
+        modified_time: parse_ticktick_date(&task.modified_time).map(|d| d.to_rfc3339()),
+    }
+}
+
+impl TaskProvider for TickTick<'_> {
+    async fn fetch_tasks(
+        &self,
+        _client: &Client,
+        _config: &TasksConfig,
+    ) -> Result<TasksData, String> {
+        let access_token = crate::oauth::ticktick::get_valid_access_token().await?;
+        let data = ticktick::fetch_ticktick(&access_token, self.0, false).await?;
+
+        Ok(TasksData {
+            provider: TaskProviderKind::TickTick,
+            tasks: data.tasks.into_iter().map(to_task).collect(),
+            lists: data
+                .projects
+                .into_iter()
+                .map(|p| TaskList {
+                    id: p.id,
+                    name: p.name,
+                    color: p.color,
+                })
+                .collect(),
+            last_updated: data.last_updated,
+        })
+    }
+
+    async fn complete_task(
+        &self,
+        _client: &Client,
+        _config: &TasksConfig,
+        list_id: &str,
+        task_id: &str,
+    ) -> Result<(), String> {
+        let access_token = crate::oauth::ticktick::get_valid_access_token().await?;
+        ticktick::complete_task(&access_token, list_id, task_id).await
+    }
+}
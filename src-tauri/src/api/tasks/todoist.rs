@@ -0,0 +1,184 @@
+use super::TaskProvider;
+use crate::api::{Task, TaskList, TasksData};
+use crate::config::{TaskProviderKind, TasksConfig};
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use reqwest::{Client, RequestBuilder};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+const TODOIST_API_BASE: &str = "https://api.todoist.com/rest/v2";
+
+/// Todoist REST API, authenticated with a personal API token
+pub struct Todoist;
+
+#[derive(Debug, Deserialize)]
+struct ApiProject {
+    id: String,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiTask {
+    id: String,
+    project_id: String,
+    content: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    labels: Vec<String>,
+    /// 1 (normal) to 4 (urgent)
+    #[serde(default)]
+    priority: i32,
+    #[serde(default)]
+    is_completed: bool,
+    due: Option<ApiDue>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiDue {
+    /// YYYY-MM-DD
+    date: String,
+    /// RFC 3339 in UTC, or without an offset for floating times
+    datetime: Option<String>,
+}
+
+fn api_token(config: &TasksConfig) -> Result<&str, String> {
+    match config.todoist.api_token.as_str() {
+        "" => Err("Todoist api_token is not configured".to_string()),
+        token => Ok(token),
+    }
+}
+
+async fn send(request: RequestBuilder, api_token: &str) -> Result<String, String> {
+    let response = request
+        .bearer_auth(api_token)
+        .send()
+        .await
+        .map_err(|e| format!("Todoist API request failed: {}", e))?;
+
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    if !status.is_success() {
+        log::error!("Todoist API error {}: {}", status, body);
+        return Err(format!("Todoist API error: {} - {}", status, body));
+    }
+
+    Ok(body)
+}
+
+async fn get<T: DeserializeOwned>(
+    client: &Client,
+    api_token: &str,
+    path: &str,
+) -> Result<T, String> {
+    let body = send(
+        client.get(format!("{}{}", TODOIST_API_BASE, path)),
+        api_token,
+    )
+    .await?;
+    serde_json::from_str(&body).map_err(|e| format!("Failed to parse Todoist {}: {}", path, e))
+}
+
+/// Todoist's 4 (urgent) down to 1 (normal), on TickTick's 5/3/1/0 scale
+fn priority(todoist_priority: i32) -> i32 {
+    match todoist_priority {
+        4 => 5,
+        3 => 3,
+        2 => 1,
+        _ => 0,
+    }
+}
+
+/// Due date as RFC 3339, or the plain date for tasks without a time
+fn due_date(due: &ApiDue) -> (String, bool) {
+    let timed = due.datetime.as_deref().and_then(|datetime| {
+        DateTime::parse_from_rfc3339(datetime)
+            .map(|d| d.with_timezone(&Local))
+            .ok()
+            .or_else(|| {
+                NaiveDateTime::parse_from_str(datetime, "%Y-%m-%dT%H:%M:%S")
+                    .ok()
+                    .and_then(|d| Local.from_local_datetime(&d).earliest())
+            })
+    });
+
+    match timed {
+        Some(datetime) => (datetime.to_rfc3339(), false),
+        None => (due.date.clone(), true),
+    }
+}
+
+impl TaskProvider for Todoist {
+    async fn fetch_tasks(
+        &self,
+        client: &Client,
+        config: &TasksConfig,
+    ) -> Result<TasksData, String> {
+        let api_token = api_token(config)?;
+
+        let (projects, tasks) = tokio::join!(
+            get::<Vec<ApiProject>>(client, api_token, "/projects"),
+            get::<Vec<ApiTask>>(client, api_token, "/tasks"),
+        );
+        let (projects, tasks) = (projects?, tasks?);
+
+        let project_names: HashMap<&str, &str> = projects
+            .iter()
+            .map(|p| (p.id.as_str(), p.name.as_str()))
+            .collect();
+
+        let tasks = tasks
+            .iter()
+            .filter(|t| !t.is_completed)
+            .map(|t| {
+                let due = t.due.as_ref().map(due_date);
+                Task {
+                    id: t.id.clone(),
+                    list_id: t.project_id.clone(),
+                    list_name: project_names
+                        .get(t.project_id.as_str())
+                        .map(|n| n.to_string()),
+                    title: t.content.clone(),
+                    notes: Some(t.description.clone()).filter(|d| !d.is_empty()),
+                    is_all_day: due.as_ref().is_some_and(|(_, all_day)| *all_day),
+                    due_date: due.map(|(date, _)| date),
+                    priority: priority(t.priority),
+                    tags: t.labels.clone(),
+                    is_completed: false,
+                    modified_time: None,
+                }
+            })
+            .collect();
+
+        Ok(TasksData {
+            provider: TaskProviderKind::Todoist,
+            tasks,
+            lists: projects
+                .into_iter()
+                .map(|p| TaskList {
+                    id: p.id,
+                    name: p.name,
+                    color: None,
+                })
+                .collect(),
+            last_updated: Local::now().to_rfc3339(),
+        })
+    }
+
+    async fn complete_task(
+        &self,
+        client: &Client,
+        config: &TasksConfig,
+        _list_id: &str,
+        task_id: &str,
+    ) -> Result<(), String> {
+        let api_token = api_token(config)?;
+        let url = format!(
+            "{}/tasks/{}/close",
+            TODOIST_API_BASE,
+            urlencoding::encode(task_id)
+        );
+        send(client.post(url), api_token).await.map(|_| ())
+    }
+}
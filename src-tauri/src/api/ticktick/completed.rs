@@ -0,0 +1,147 @@
+use super::parse_ticktick_date;
+use crate::api::{CompletedRange, CompletedTasks, CompletionStats, DailyCount, TickTickTask};
+use crate::config::get_config_dir;
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// How long completions are kept for stats and streaks
+const RETENTION_DAYS: i64 = 366;
+
+/// Tasks completed from the dashboard, or confirmed completed after they
+/// dropped out of a sync
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CompletedLog {
+    tasks: Vec<TickTickTask>,
+}
+
+fn log_path() -> Result<PathBuf, String> {
+    get_config_dir()
+        .map(|p| p.join("ticktick_completed.json"))
+        .map_err(|e| e.to_string())
+}
+
+/// The local day a task was completed
+fn completed_on(task: &TickTickTask) -> Option<NaiveDate> {
+    parse_ticktick_date(task.completed_time.as_deref()?)
+        .map(|d| d.with_timezone(&Local).date_naive())
+}
+
+impl CompletedLog {
+    pub fn load() -> Self {
+        log_path()
+            .ok()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let path = log_path()?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Failed to create config dir: {}", e))?;
+        }
+
+        let content = serde_json::to_string(self)
+            .map_err(|e| format!("Failed to serialize completed tasks: {}", e))?;
+        fs::write(&path, content).map_err(|e| format!("Failed to write completed tasks: {}", e))
+    }
+
+    /// Add a completed task, once per task and day. Repeating tasks keep
+    /// their id, so they can appear once for each day they were done
+    pub fn record(&mut self, mut task: TickTickTask) {
+        task.is_completed = true;
+        if task.completed_time.is_none() {
+            task.completed_time = Some(Local::now().format("%Y-%m-%dT%H:%M:%S%.3f%z").to_string());
+        }
+
+        let day = completed_on(&task);
+        if self
+            .tasks
+            .iter()
+            .any(|t| t.id == task.id && completed_on(t) == day)
+        {
+            return;
+        }
+
+        let cutoff = Local::now().date_naive() - Duration::days(RETENTION_DAYS);
+        self.tasks
+            .retain(|t| completed_on(t).filter(|d| *d < cutoff).is_none());
+        self.tasks.push(task);
+    }
+
+    /// Whether a task has been logged on any day
+    pub fn contains(&self, task_id: &str) -> bool {
+        self.tasks.iter().any(|t| t.id == task_id)
+    }
+
+    fn daily_counts(&self) -> BTreeMap<NaiveDate, usize> {
+        let mut counts = BTreeMap::new();
+        for day in self.tasks.iter().filter_map(completed_on) {
+            *counts.entry(day).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    fn stats(&self, since: NaiveDate, today: NaiveDate) -> CompletionStats {
+        let counts = self.daily_counts();
+        let count = |day: &NaiveDate| counts.get(day).copied().unwrap_or(0);
+
+        let monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+
+        let mut day = if count(&today) > 0 {
+            today
+        } else {
+            today - Duration::days(1)
+        };
+        let mut streak_days = 0;
+        while count(&day) > 0 {
+            streak_days += 1;
+            day -= Duration::days(1);
+        }
+
+        CompletionStats {
+            today: count(&today),
+            this_week: counts.range(monday..=today).map(|(_, n)| n).sum(),
+            streak_days,
+            daily: since
+                .iter_days()
+                .take_while(|d| *d <= today)
+                .map(|d| DailyCount {
+                    date: d.format("%Y-%m-%d").to_string(),
+                    count: count(&d),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn query(&self, range: CompletedRange) -> CompletedTasks {
+        let today = Local::now().date_naive();
+        let since = match range {
+            CompletedRange::Today => today,
+            CompletedRange::Week => {
+                today - Duration::days(today.weekday().num_days_from_monday() as i64)
+            }
+            CompletedRange::Month => today - Duration::days(29),
+        };
+
+        let mut tasks: Vec<TickTickTask> = self
+            .tasks
+            .iter()
+            .filter(|t| completed_on(t).is_some_and(|d| d >= since && d <= today))
+            .cloned()
+            .collect();
+        // Newest first. Completion times carry different offsets (TickTick's
+        // +0000 and our local one), so compare them as instants
+        tasks.sort_by_key(|t| Reverse(t.completed_time.as_deref().and_then(parse_ticktick_date)));
+
+        CompletedTasks {
+            range,
+            tasks,
+            stats: self.stats(since, today),
+        }
+    }
+}
@@ -0,0 +1,1205 @@
+mod completed;
+mod outbox;
+mod query;
+mod store;
+
+use super::{
+    ChecklistItem, ChecklistProgress, CompletedRange, CompletedTasks, NewTickTickTask,
+    PendingTaskAction, ProjectFailure, TaskAction, TaskChanges, TickTickData, TickTickProject,
+    TickTickTask, TickTickTaskUpdate,
+};
+use crate::config::TickTickConfig;
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+use reqwest::header::RETRY_AFTER;
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, Semaphore};
+use tokio::task::JoinSet;
+
+pub use completed::CompletedLog;
+pub use outbox::Outbox;
+pub use query::query_tasks;
+pub use store::TaskStore;
+
+const TICKTICK_API_BASE: &str = "https://api.ticktick.com/open/v1";
+
+/// TickTick's status for a completed task
+const STATUS_COMPLETED: i32 = 2;
+/// Cap on lookups per sync when checking whether removed tasks were completed
+const MAX_COMPLETION_CHECKS: usize = 20;
+/// Total time a sync spends on those lookups
+const COMPLETION_CHECK_BUDGET: Duration = Duration::from_secs(10);
+
+// Stay under TickTick's rate limit while still fetching projects in parallel
+const MAX_CONCURRENT_REQUESTS: usize = 4;
+const MAX_ATTEMPTS: u32 = 4;
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Prefix for errors where TickTick couldn't be reached at all, as opposed to
+/// an error response
+const REQUEST_FAILED: &str = "TickTick API request failed";
+/// Prefix for timeouts, rate limits and server errors, which are worth
+/// retrying later unlike other error responses
+const UNAVAILABLE: &str = "TickTick API unavailable";
+
+/// Held while replaying or adding to the outbox, so changes keep their order,
+/// and while a sync reads and writes the task store or completed log, so a
+/// change can't land between them being read and saved. Syncs don't hold it
+/// while fetching, so dashboard changes never wait on a full sync
+static OUTBOX_LOCK: Mutex<()> = Mutex::const_new(());
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiProject {
+    id: String,
+    name: String,
+    color: Option<String>,
+    sort_order: i64,
+    #[serde(default)]
+    closed: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiTask {
+    id: String,
+    title: Option<String>,
+    content: Option<String>,
+    #[serde(default)]
+    priority: i32,
+    #[serde(default)]
+    status: i32,
+    due_date: Option<String>,
+    start_date: Option<String>,
+    project_id: String,
+    #[serde(default)]
+    tags: Vec<String>,
+    // These fields may not be present in all API responses
+    #[serde(default)]
+    created_time: Option<String>,
+    #[serde(default)]
+    modified_time: Option<String>,
+    desc: Option<String>,
+    #[serde(default)]
+    items: Vec<ApiChecklistItem>,
+    repeat_flag: Option<String>,
+    /// iCalendar triggers such as "TRIGGER:-PT30M"
+    #[serde(default)]
+    reminders: Vec<String>,
+    time_zone: Option<String>,
+    #[serde(default)]
+    is_all_day: bool,
+    completed_time: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiChecklistItem {
+    id: String,
+    title: Option<String>,
+    #[serde(default)]
+    status: i32,
+    #[serde(default)]
+    sort_order: i64,
+}
+
+/// Body for the create and update endpoints. Unset fields are left out so an
+/// update doesn't clear them
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiTaskBody {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    project_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    due_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    is_all_day: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    priority: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProjectData {
+    #[serde(default)]
+    tasks: Vec<ApiTask>,
+}
+
+/// Parse TickTick's date format ("2024-01-31T09:00:00.000+0000")
+pub(crate) fn parse_ticktick_date(value: &str) -> Option<DateTime<FixedOffset>> {
+    DateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f%z")
+        .or_else(|_| DateTime::parse_from_rfc3339(value))
+        .ok()
+}
+
+/// The calendar day of an all-day date in the task's own timezone
+fn all_day_date(value: &str, time_zone: Option<&str>) -> Option<NaiveDate> {
+    let date = parse_ticktick_date(value)?;
+    Some(match time_zone.and_then(|tz| tz.parse::<Tz>().ok()) {
+        Some(tz) => date.with_timezone(&tz).date_naive(),
+        None => date.with_timezone(&Local).date_naive(),
+    })
+}
+
+/// Minutes from an iCalendar trigger such as "TRIGGER:-PT30M" or "TRIGGER:P0DT9H0M0S"
+fn reminder_offset(trigger: &str) -> Option<i64> {
+    let duration = trigger.strip_prefix("TRIGGER:").unwrap_or(trigger);
+    let (sign, duration) = match duration.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, duration.strip_prefix('+').unwrap_or(duration)),
+    };
+    let duration = duration.strip_prefix('P')?;
+
+    let mut minutes = 0;
+    let mut number = String::new();
+    for c in duration.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'T' => {}
+            unit => {
+                let value: i64 = number.parse().ok()?;
+                number.clear();
+                minutes += match unit {
+                    'W' => value * 7 * 24 * 60,
+                    'D' => value * 24 * 60,
+                    'H' => value * 60,
+                    'M' => value,
+                    'S' => 0,
+                    _ => return None,
+                };
+            }
+        }
+    }
+
+    Some(sign * minutes)
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|v| !v.trim().is_empty())
+}
+
+fn to_task(task: ApiTask, project_map: &HashMap<String, String>) -> TickTickTask {
+    let title = non_empty(task.title);
+    let content = non_empty(task.content);
+    // Untitled tasks show their content instead, so it isn't repeated as notes
+    let notes = title.as_ref().and(content.clone()).or(non_empty(task.desc));
+
+    let mut checklist: Vec<ChecklistItem> = task
+        .items
+        .into_iter()
+        .map(|item| ChecklistItem {
+            id: item.id,
+            title: item.title.unwrap_or_default(),
+            is_completed: item.status != 0,
+            sort_order: item.sort_order,
+        })
+        .collect();
+    checklist.sort_by_key(|item| item.sort_order);
+
+    let checklist_progress = (!checklist.is_empty()).then(|| ChecklistProgress {
+        completed: checklist.iter().filter(|item| item.is_completed).count(),
+        total: checklist.len(),
+    });
+
+    let due_day = task
+        .due_date
+        .as_deref()
+        .filter(|_| task.is_all_day)
+        .and_then(|due| all_day_date(due, task.time_zone.as_deref()))
+        .map(|day| day.format("%Y-%m-%d").to_string());
+
+    TickTickTask {
+        id: task.id,
+        title: title
+            .or(content)
+            .unwrap_or_else(|| "Untitled Task".to_string()),
+        is_completed: task.status != 0,
+        priority: task.priority,
+        due_date: task.due_date,
+        start_date: task.start_date,
+        project_name: project_map.get(&task.project_id).cloned(),
+        project_id: task.project_id,
+        tags: task.tags,
+        created_time: task.created_time.unwrap_or_default(),
+        modified_time: task.modified_time.unwrap_or_default(),
+        notes,
+        checklist,
+        checklist_progress,
+        recurrence: non_empty(task.repeat_flag),
+        is_all_day: task.is_all_day,
+        time_zone: task.time_zone,
+        due_day,
+        reminder_offsets: task
+            .reminders
+            .iter()
+            .filter_map(|trigger| reminder_offset(trigger))
+            .collect(),
+        completed_time: task.completed_time,
+    }
+}
+
+/// Convert an RFC 3339 timestamp or a plain date into TickTick's date format
+/// ("2024-01-31T09:00:00+0000"), plus whether it's an all-day date
+fn ticktick_date(value: &str) -> Result<(String, bool), String> {
+    if let Ok(date_time) = DateTime::parse_from_rfc3339(value) {
+        return Ok((date_time.format("%Y-%m-%dT%H:%M:%S%z").to_string(), false));
+    }
+
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid due date '{}'", value))?;
+    let midnight = date
+        .and_hms_opt(0, 0, 0)
+        .and_then(|d| Local.from_local_datetime(&d).earliest())
+        .ok_or_else(|| format!("Invalid due date '{}'", value))?;
+
+    Ok((midnight.format("%Y-%m-%dT%H:%M:%S%z").to_string(), true))
+}
+
+/// TickTick only accepts 0 (none), 1 (low), 3 (medium) and 5 (high)
+fn check_priority(priority: i32) -> Result<(), String> {
+    if matches!(priority, 0 | 1 | 3 | 5) {
+        Ok(())
+    } else {
+        Err(format!("Invalid TickTick priority {}", priority))
+    }
+}
+
+/// Whether TickTick couldn't be reached or can't handle requests right now,
+/// so changes should wait in the outbox rather than fail
+fn is_offline(error: &str) -> bool {
+    error.starts_with(REQUEST_FAILED) || error.starts_with(UNAVAILABLE)
+}
+
+fn is_retryable(status: StatusCode) -> bool {
+    status == StatusCode::REQUEST_TIMEOUT
+        || status == StatusCode::TOO_MANY_REQUESTS
+        || status.is_server_error()
+}
+
+fn status_error(status: StatusCode, body: &str) -> String {
+    log::error!("TickTick API error {}: {}", status, body);
+    if is_retryable(status) {
+        format!("{}: {} - {}", UNAVAILABLE, status, body)
+    } else {
+        format!("TickTick API error: {} - {}", status, body)
+    }
+}
+
+fn check_token(access_token: &str) -> Result<(), String> {
+    if access_token.is_empty() {
+        return Err("TickTick access token not configured".to_string());
+    }
+    Ok(())
+}
+
+async fn send(request: RequestBuilder, access_token: &str) -> Result<String, String> {
+    let response = request
+        .header("Authorization", format!("Bearer {}", access_token))
+        .send()
+        .await
+        .map_err(|e| format!("{}: {}", REQUEST_FAILED, e))?;
+
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    if !status.is_success() {
+        return Err(status_error(status, &body));
+    }
+
+    Ok(body)
+}
+
+/// Delay requested by a 429 or 503, given in seconds or as an HTTP date
+fn retry_after(response: &Response) -> Option<Duration> {
+    let value = response.headers().get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    (date.with_timezone(&Utc) - Utc::now()).to_std().ok()
+}
+
+/// GET a TickTick endpoint, retrying rate limits, server errors and network
+/// failures with exponential backoff (or the server's `Retry-After`)
+async fn get_with_retry(client: &Client, access_token: &str, url: &str) -> Result<String, String> {
+    let mut backoff = INITIAL_BACKOFF;
+
+    for attempt in 1..=MAX_ATTEMPTS {
+        let last_attempt = attempt == MAX_ATTEMPTS;
+        let result = client
+            .get(url)
+            .header("Authorization", format!("Bearer {}", access_token))
+            .send()
+            .await;
+
+        let delay = match result {
+            Ok(response) if response.status().is_success() => {
+                return response
+                    .text()
+                    .await
+                    .map_err(|e| format!("Failed to read response: {}", e));
+            }
+            Ok(response) => {
+                let status = response.status();
+                let delay = retry_after(&response);
+                if !is_retryable(status) || last_attempt {
+                    let body = response.text().await.unwrap_or_default();
+                    return Err(status_error(status, &body));
+                }
+                log::warn!("TickTick: {} from {}, retrying", status, url);
+                delay
+            }
+            Err(e) if last_attempt => return Err(format!("{}: {}", REQUEST_FAILED, e)),
+            Err(e) => {
+                log::warn!("TickTick: Request to {} failed, retrying: {}", url, e);
+                None
+            }
+        };
+
+        tokio::time::sleep(delay.unwrap_or(backoff).min(MAX_BACKOFF)).await;
+        backoff *= 2;
+    }
+
+    unreachable!("the last attempt always returns")
+}
+
+async fn fetch_projects(client: &Client, access_token: &str) -> Result<Vec<ApiProject>, String> {
+    let url = format!("{}/project", TICKTICK_API_BASE);
+    let body = get_with_retry(client, access_token, &url).await?;
+
+    serde_json::from_str(&body)
+        .map_err(|e| format!("Failed to parse TickTick projects: {}", e))
+}
+
+async fn fetch_project_data(
+    client: &Client,
+    access_token: &str,
+    project_id: &str,
+) -> Result<ProjectData, String> {
+    let url = format!("{}/project/{}/data", TICKTICK_API_BASE, project_id);
+    let body = get_with_retry(client, access_token, &url).await?;
+
+    serde_json::from_str(&body).map_err(|e| format!("Failed to parse TickTick project data: {}", e))
+}
+
+/// Fetch every project's data concurrently, keeping the project order
+async fn fetch_all_project_data(
+    client: &Client,
+    access_token: &str,
+    projects: &[TickTickProject],
+) -> Result<Vec<Result<ProjectData, String>>, String> {
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_REQUESTS));
+    let mut requests = JoinSet::new();
+
+    for (index, project) in projects.iter().enumerate() {
+        let client = client.clone();
+        let access_token = access_token.to_string();
+        let project_id = project.id.clone();
+        let semaphore = semaphore.clone();
+
+        requests.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let result = fetch_project_data(&client, &access_token, &project_id).await;
+            (index, result)
+        });
+    }
+
+    let mut results: Vec<Option<Result<ProjectData, String>>> =
+        (0..projects.len()).map(|_| None).collect();
+
+    while let Some(joined) = requests.join_next().await {
+        let (index, result) = joined.map_err(|e| format!("TickTick fetch task failed: {}", e))?;
+        results[index] = Some(result);
+    }
+
+    Ok(results.into_iter().flatten().collect())
+}
+
+/// The store as of the last sync, without contacting TickTick. Tasks with a
+/// completion still queued are left out; they stay in the store until
+/// TickTick accepts it, so a rejected or discarded completion brings them back
+fn snapshot(store: &TaskStore, outbox: &Outbox, offline: bool) -> TickTickData {
+    TickTickData {
+        tasks: store
+            .tasks()
+            .into_iter()
+            .filter(|t| !outbox.is_completing(&t.id))
+            .collect(),
+        projects: store.projects.clone(),
+        failed_projects: store.failed_projects.clone(),
+        changes: TaskChanges::default(),
+        pending_actions: outbox.actions(),
+        offline,
+        last_updated: store.last_sync.clone().unwrap_or_default(),
+    }
+}
+
+/// Sync open tasks into the local store and return them with the changes
+/// since the previous sync. Unless `force` is set, a sync within
+/// `min_sync_interval_seconds` of the last one returns the store as-is.
+/// Queued changes are sent first; while TickTick can't be reached the store
+/// is returned with them still pending
+pub async fn fetch_ticktick(
+    access_token: &str,
+    config: &TickTickConfig,
+    force: bool,
+) -> Result<TickTickData, String> {
+    check_token(access_token)?;
+
+    let client = Client::new();
+    let has_synced = {
+        let _lock = OUTBOX_LOCK.lock().await;
+        let outbox = replay_outbox(&client, access_token).await;
+
+        let store = TaskStore::load();
+        if outbox.has_pending() {
+            return Ok(snapshot(&store, &outbox, true));
+        }
+        let min_interval = chrono::Duration::seconds(config.min_sync_interval_seconds as i64);
+        if !force && store.synced_within(min_interval) {
+            return Ok(snapshot(&store, &outbox, false));
+        }
+        store.last_sync.is_some()
+    };
+
+    // Fetch all projects
+    let api_projects = match fetch_projects(&client, access_token).await {
+        Ok(projects) => projects,
+        Err(e) if is_offline(&e) && has_synced => {
+            log::warn!("TickTick: Offline, showing the last sync: {}", e);
+            let _lock = OUTBOX_LOCK.lock().await;
+            return Ok(snapshot(&TaskStore::load(), &Outbox::load(), true));
+        }
+        Err(e) => return Err(e),
+    };
+
+    // Filter out closed projects
+    let projects: Vec<TickTickProject> = api_projects
+        .iter()
+        .filter(|p| !p.closed)
+        .map(|p| TickTickProject {
+            id: p.id.clone(),
+            name: p.name.clone(),
+            color: p.color.clone(),
+            sort_order: p.sort_order,
+        })
+        .collect();
+
+    // Create a map of project id to name
+    let project_map: HashMap<String, String> = projects
+        .iter()
+        .map(|p| (p.id.clone(), p.name.clone()))
+        .collect();
+
+    // Fetch tasks for each project
+    let mut all_tasks = Vec::new();
+    let mut failed_projects = Vec::new();
+
+    let results = fetch_all_project_data(&client, access_token, &projects).await?;
+    for (project, result) in projects.iter().zip(results) {
+        match result {
+            Ok(project_data) => {
+                // Filter out completed tasks (status 0 = not completed)
+                all_tasks.extend(
+                    project_data
+                        .tasks
+                        .into_iter()
+                        .filter(|task| task.status == 0)
+                        .map(|task| to_task(task, &project_map)),
+                );
+            }
+            Err(e) => {
+                log::warn!("TickTick: Failed to fetch project '{}': {}", project.name, e);
+                failed_projects.push(ProjectFailure {
+                    project_id: project.id.clone(),
+                    project_name: project.name.clone(),
+                    error: e,
+                });
+            }
+        }
+    }
+
+    // Changes made during the fetch are in the store and outbox by now
+    let (data, mut previous) = {
+        let _lock = OUTBOX_LOCK.lock().await;
+        let mut store = TaskStore::load();
+        let outbox = Outbox::load();
+
+        let previous: HashMap<String, TickTickTask> = store
+            .tasks()
+            .into_iter()
+            .map(|t| (t.id.clone(), t))
+            .collect();
+        let changes = store.merge(projects, all_tasks, failed_projects);
+        if let Err(e) = store.save() {
+            log::warn!("TickTick: Failed to save task store: {}", e);
+        }
+
+        let data = TickTickData {
+            changes,
+            ..snapshot(&store, &outbox, false)
+        };
+        (data, previous)
+    };
+
+    let removed: Vec<TickTickTask> = data
+        .changes
+        .removed
+        .iter()
+        .filter_map(|id| previous.remove(id))
+        .collect();
+    record_completions(&client, access_token, &removed, &project_map).await;
+
+    Ok(data)
+}
+
+async fn fetch_task(
+    client: &Client,
+    access_token: &str,
+    project_id: &str,
+    task_id: &str,
+) -> Result<ApiTask, String> {
+    let url = format!(
+        "{}/project/{}/task/{}",
+        TICKTICK_API_BASE,
+        urlencoding::encode(project_id),
+        urlencoding::encode(task_id)
+    );
+    let body = get_with_retry(client, access_token, &url).await?;
+
+    serde_json::from_str(&body).map_err(|e| format!("Failed to parse TickTick task: {}", e))
+}
+
+/// Look up tasks that dropped out of a sync and log the ones that were
+/// completed rather than deleted. Tasks already logged (completed from the
+/// dashboard) are skipped, and lookups still running after
+/// `COMPLETION_CHECK_BUDGET` are dropped so they don't hold up the sync
+async fn record_completions(
+    client: &Client,
+    access_token: &str,
+    removed: &[TickTickTask],
+    project_map: &HashMap<String, String>,
+) {
+    let log = CompletedLog::load();
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_REQUESTS));
+    let mut lookups = JoinSet::new();
+
+    for task in removed
+        .iter()
+        .filter(|t| !log.contains(&t.id))
+        .take(MAX_COMPLETION_CHECKS)
+    {
+        let client = client.clone();
+        let access_token = access_token.to_string();
+        let project_id = task.project_id.clone();
+        let task_id = task.id.clone();
+        let semaphore = semaphore.clone();
+
+        lookups.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let result = fetch_task(&client, &access_token, &project_id, &task_id).await;
+            (task_id, result)
+        });
+    }
+
+    if lookups.is_empty() {
+        return;
+    }
+
+    let mut completed = Vec::new();
+    let collect = async {
+        while let Some(joined) = lookups.join_next().await {
+            match joined {
+                Ok((_, Ok(api_task))) if api_task.status == STATUS_COMPLETED => {
+                    completed.push(to_task(api_task, project_map));
+                }
+                Ok((_, Ok(_))) => {}
+                // Deleted tasks come back as errors and are skipped
+                Ok((task_id, Err(e))) => {
+                    log::debug!("TickTick: Skipping removed task {}: {}", task_id, e)
+                }
+                Err(e) => log::warn!("TickTick: Completion lookup failed: {}", e),
+            }
+        }
+    };
+    if tokio::time::timeout(COMPLETION_CHECK_BUDGET, collect)
+        .await
+        .is_err()
+    {
+        log::warn!(
+            "TickTick: Stopped checking removed tasks after {}s",
+            COMPLETION_CHECK_BUDGET.as_secs()
+        );
+    }
+    if completed.is_empty() {
+        return;
+    }
+
+    // Reload, since completions from the dashboard may have been logged
+    // while the lookups ran
+    let _lock = OUTBOX_LOCK.lock().await;
+    let mut log = CompletedLog::load();
+    for task in completed {
+        log.record(task);
+    }
+    if let Err(e) = log.save() {
+        log::warn!("TickTick: Failed to save completed tasks: {}", e);
+    }
+}
+
+/// Apply a change made from the dashboard to the local store, so the next
+/// sync doesn't report it back as a remote change
+fn update_store(change: impl FnOnce(&mut TaskStore)) {
+    let mut store = TaskStore::load();
+    change(&mut store);
+    if let Err(e) = store.save() {
+        log::warn!("TickTick: Failed to save task store: {}", e);
+    }
+}
+
+/// Drop a completed task from the store and add it to the completed log
+fn record_completed(task_id: &str) {
+    let mut completed = None;
+    update_store(|store| completed = store.remove(task_id));
+
+    if let Some(task) = completed {
+        let mut log = CompletedLog::load();
+        log.record(task);
+        if let Err(e) = log.save() {
+            log::warn!("TickTick: Failed to save completed tasks: {}", e);
+        }
+    }
+}
+
+fn create_body(task: &NewTickTickTask) -> Result<ApiTaskBody, String> {
+    let title = task.title.trim();
+    if title.is_empty() {
+        return Err("Task title is empty".to_string());
+    }
+    check_priority(task.priority)?;
+
+    let due = task.due_date.as_deref().map(ticktick_date).transpose()?;
+    Ok(ApiTaskBody {
+        project_id: task.project_id.clone(),
+        title: Some(title.to_string()),
+        due_date: due.as_ref().map(|(date, _)| date.clone()),
+        is_all_day: due.as_ref().map(|(_, all_day)| *all_day),
+        priority: Some(task.priority),
+        ..Default::default()
+    })
+}
+
+fn update_body(update: &TickTickTaskUpdate) -> Result<ApiTaskBody, String> {
+    if let Some(priority) = update.priority {
+        check_priority(priority)?;
+    }
+    let title = update.title.as_ref().map(|t| t.trim().to_string());
+    if title.as_deref() == Some("") {
+        return Err("Task title is empty".to_string());
+    }
+
+    let due = update.due_date.as_deref().map(ticktick_date).transpose()?;
+    Ok(ApiTaskBody {
+        id: Some(update.id.clone()),
+        project_id: update.project_id.clone(),
+        title,
+        due_date: due.as_ref().map(|(date, _)| date.clone()),
+        is_all_day: due.as_ref().map(|(_, all_day)| *all_day),
+        priority: update.priority,
+    })
+}
+
+async fn send_complete(
+    client: &Client,
+    access_token: &str,
+    project_id: &str,
+    task_id: &str,
+) -> Result<(), String> {
+    let url = format!(
+        "{}/project/{}/task/{}/complete",
+        TICKTICK_API_BASE,
+        urlencoding::encode(project_id),
+        urlencoding::encode(task_id)
+    );
+    send(client.post(url), access_token).await.map(|_| ())
+}
+
+async fn send_create(
+    client: &Client,
+    access_token: &str,
+    task: &NewTickTickTask,
+) -> Result<TickTickTask, String> {
+    let body = create_body(task)?;
+    let response = send(
+        client
+            .post(format!("{}/task", TICKTICK_API_BASE))
+            .json(&body),
+        access_token,
+    )
+    .await?;
+
+    let created: ApiTask = serde_json::from_str(&response)
+        .map_err(|e| format!("Failed to parse created TickTick task: {}", e))?;
+    Ok(to_task(created, &HashMap::new()))
+}
+
+async fn send_update(
+    client: &Client,
+    access_token: &str,
+    update: &TickTickTaskUpdate,
+) -> Result<TickTickTask, String> {
+    let body = update_body(update)?;
+    let url = format!(
+        "{}/task/{}",
+        TICKTICK_API_BASE,
+        urlencoding::encode(&update.id)
+    );
+    let response = send(client.post(url).json(&body), access_token).await?;
+
+    let updated: ApiTask = serde_json::from_str(&response)
+        .map_err(|e| format!("Failed to parse updated TickTick task: {}", e))?;
+    Ok(to_task(updated, &HashMap::new()))
+}
+
+/// Whether the task was edited in TickTick after a change to it was queued.
+/// The later edit wins, so the queued change is dropped
+fn edited_since(server: &ApiTask, pending: &PendingTaskAction) -> bool {
+    let (Some(base), Some(modified)) = (&pending.base_modified_time, &server.modified_time) else {
+        return false;
+    };
+    if base == modified {
+        return false;
+    }
+
+    let queued_at = DateTime::parse_from_rfc3339(&pending.queued_at).ok();
+    match (parse_ticktick_date(modified), queued_at) {
+        (Some(modified), Some(queued_at)) => modified > queued_at,
+        _ => false,
+    }
+}
+
+enum Replayed {
+    Done,
+    /// A task created offline and the id TickTick gave it
+    Created {
+        local_id: String,
+        task_id: String,
+    },
+    /// Dropped in favour of the task's state in TickTick
+    Conflict(String),
+}
+
+async fn replay(
+    client: &Client,
+    access_token: &str,
+    pending: &PendingTaskAction,
+) -> Result<Replayed, String> {
+    match &pending.action {
+        TaskAction::Complete {
+            project_id,
+            task_id,
+        } => {
+            let server = fetch_task(client, access_token, project_id, task_id).await?;
+            if server.status == STATUS_COMPLETED {
+                record_completed(task_id);
+                return Ok(Replayed::Done);
+            }
+            if edited_since(&server, pending) {
+                return Ok(Replayed::Conflict(
+                    "Edited in TickTick after it was completed offline".to_string(),
+                ));
+            }
+
+            send_complete(client, access_token, project_id, task_id).await?;
+            record_completed(task_id);
+            Ok(Replayed::Done)
+        }
+        TaskAction::Create { task, local_id } => {
+            let created = send_create(client, access_token, task).await?;
+            let task_id = created.id.clone();
+            update_store(|store| {
+                store.remove(local_id);
+                store.upsert(created);
+            });
+            Ok(Replayed::Created {
+                local_id: local_id.clone(),
+                task_id,
+            })
+        }
+        TaskAction::Update { update } => {
+            let server = fetch_task(client, access_token, &update.project_id, &update.id).await?;
+            if server.status == STATUS_COMPLETED {
+                return Ok(Replayed::Conflict(
+                    "Completed in TickTick before this edit was sent".to_string(),
+                ));
+            }
+            if edited_since(&server, pending) {
+                return Ok(Replayed::Conflict(
+                    "Edited in TickTick after this offline edit".to_string(),
+                ));
+            }
+
+            let updated = send_update(client, access_token, update).await?;
+            update_store(|store| store.upsert(updated));
+            Ok(Replayed::Done)
+        }
+    }
+}
+
+/// Send queued changes in the order they were made, stopping at the first
+/// one that can't reach TickTick so later changes never overtake it. Changes
+/// TickTick rejects, or that lose a conflict, stay queued with their error.
+/// Callers hold `OUTBOX_LOCK`
+async fn replay_outbox(client: &Client, access_token: &str) -> Outbox {
+    let mut outbox = Outbox::load();
+    if !outbox.has_pending() {
+        return outbox;
+    }
+
+    while let Some(id) = outbox.first_pending() {
+        let Some(pending) = outbox.get_mut(&id) else {
+            break;
+        };
+        pending.attempts += 1;
+        let pending = pending.clone();
+
+        let error = match replay(client, access_token, &pending).await {
+            Ok(Replayed::Done) => {
+                outbox.remove(&id);
+                continue;
+            }
+            Ok(Replayed::Created { local_id, task_id }) => {
+                outbox.remove(&id);
+                outbox.replace_task_id(&local_id, &task_id);
+                continue;
+            }
+            Ok(Replayed::Conflict(reason)) => reason,
+            Err(e) if is_offline(&e) => {
+                log::warn!("TickTick: Still offline, keeping queued changes: {}", e);
+                break;
+            }
+            Err(e) => e,
+        };
+
+        log::warn!("TickTick: Queued change {} failed: {}", id, error);
+        if let Some(pending) = outbox.get_mut(&id) {
+            pending.error = Some(error);
+        }
+    }
+
+    if let Err(e) = outbox.save() {
+        log::warn!("TickTick: Failed to save task outbox: {}", e);
+    }
+    outbox
+}
+
+/// Queue a change to send once TickTick is reachable. Fails if the queue
+/// can't be saved, since the change would otherwise be lost
+fn queue(
+    outbox: &mut Outbox,
+    action: TaskAction,
+    base_modified_time: Option<String>,
+) -> Result<(), String> {
+    outbox.push(action, base_modified_time);
+    outbox.save()
+}
+
+fn ticktick_now() -> String {
+    Local::now().format("%Y-%m-%dT%H:%M:%S%.3f%z").to_string()
+}
+
+pub async fn complete_task(
+    access_token: &str,
+    project_id: &str,
+    task_id: &str,
+) -> Result<(), String> {
+    check_token(access_token)?;
+
+    let client = Client::new();
+    let _lock = OUTBOX_LOCK.lock().await;
+    let mut outbox = replay_outbox(&client, access_token).await;
+
+    // Anything still queued has to go first
+    if !outbox.has_pending() {
+        match send_complete(&client, access_token, project_id, task_id).await {
+            Ok(()) => {
+                record_completed(task_id);
+                return Ok(());
+            }
+            Err(e) if !is_offline(&e) => return Err(e),
+            Err(e) => log::warn!("TickTick: Offline, queueing completion: {}", e),
+        }
+    }
+
+    let base_modified_time = TaskStore::load()
+        .get(task_id)
+        .map(|t| t.modified_time.clone());
+    queue(
+        &mut outbox,
+        TaskAction::Complete {
+            project_id: project_id.to_string(),
+            task_id: task_id.to_string(),
+        },
+        base_modified_time,
+    )?;
+
+    Ok(())
+}
+
+/// Create a task. While offline it's queued and a placeholder with a local
+/// id is returned until TickTick creates it
+pub async fn create_task(
+    access_token: &str,
+    task: NewTickTickTask,
+) -> Result<TickTickTask, String> {
+    check_token(access_token)?;
+    let body = create_body(&task)?;
+
+    let client = Client::new();
+    let _lock = OUTBOX_LOCK.lock().await;
+    let mut outbox = replay_outbox(&client, access_token).await;
+
+    if !outbox.has_pending() {
+        match send_create(&client, access_token, &task).await {
+            Ok(created) => {
+                update_store(|store| store.upsert(created.clone()));
+                return Ok(created);
+            }
+            Err(e) if !is_offline(&e) => return Err(e),
+            Err(e) => log::warn!("TickTick: Offline, queueing new task: {}", e),
+        }
+    }
+
+    let local_id = format!("{}{}", outbox::LOCAL_ID_PREFIX, outbox.next_id());
+    let is_all_day = body.is_all_day.unwrap_or(false);
+    let placeholder = TickTickTask {
+        id: local_id.clone(),
+        title: body.title.unwrap_or_default(),
+        priority: task.priority,
+        due_date: body.due_date,
+        due_day: task.due_date.clone().filter(|_| is_all_day),
+        is_all_day,
+        project_id: task.project_id.clone(),
+        created_time: ticktick_now(),
+        modified_time: ticktick_now(),
+        ..Default::default()
+    };
+
+    queue(&mut outbox, TaskAction::Create { task, local_id }, None)?;
+    update_store(|store| store.upsert(placeholder.clone()));
+
+    Ok(placeholder)
+}
+
+/// Update a task. While offline the change is queued and applied to the
+/// local copy, which is returned
+pub async fn update_task(
+    access_token: &str,
+    update: TickTickTaskUpdate,
+) -> Result<TickTickTask, String> {
+    check_token(access_token)?;
+    let body = update_body(&update)?;
+
+    let client = Client::new();
+    let _lock = OUTBOX_LOCK.lock().await;
+    let mut outbox = replay_outbox(&client, access_token).await;
+
+    if !outbox.has_pending() {
+        match send_update(&client, access_token, &update).await {
+            Ok(updated) => {
+                update_store(|store| store.upsert(updated.clone()));
+                return Ok(updated);
+            }
+            Err(e) if !is_offline(&e) => return Err(e),
+            Err(e) => log::warn!("TickTick: Offline, queueing task update: {}", e),
+        }
+    }
+
+    let mut task = TaskStore::load()
+        .get(&update.id)
+        .cloned()
+        .ok_or_else(|| format!("Task {} isn't in the local store", update.id))?;
+    // Placeholders have nothing in TickTick to conflict with yet
+    let base_modified_time =
+        (!task.id.starts_with(outbox::LOCAL_ID_PREFIX)).then(|| task.modified_time.clone());
+
+    if let Some(title) = body.title {
+        task.title = title;
+    }
+    if let Some(due_date) = body.due_date {
+        task.is_all_day = body.is_all_day.unwrap_or(false);
+        task.due_day = update.due_date.clone().filter(|_| task.is_all_day);
+        task.due_date = Some(due_date);
+    }
+    if let Some(priority) = body.priority {
+        task.priority = priority;
+    }
+    task.modified_time = ticktick_now();
+
+    queue(
+        &mut outbox,
+        TaskAction::Update { update },
+        base_modified_time,
+    )?;
+    update_store(|store| store.upsert(task.clone()));
+
+    Ok(task)
+}
+
+/// Changes still waiting to reach TickTick, and ones it rejected
+pub fn get_pending_actions() -> Vec<PendingTaskAction> {
+    Outbox::load().actions()
+}
+
+/// Send queued changes now, e.g. when the network comes back
+pub async fn replay_pending_actions(access_token: &str) -> Result<Vec<PendingTaskAction>, String> {
+    check_token(access_token)?;
+
+    let _lock = OUTBOX_LOCK.lock().await;
+    Ok(replay_outbox(&Client::new(), access_token).await.actions())
+}
+
+/// Drop a queued or failed change without sending it. The next sync undoes
+/// it locally
+pub async fn discard_pending_action(id: &str) -> Result<(), String> {
+    let _lock = OUTBOX_LOCK.lock().await;
+    let mut outbox = Outbox::load();
+    if !outbox.remove(id) {
+        return Err(format!("No queued task change {}", id));
+    }
+    outbox.save()
+}
+
+/// Tasks completed in `range`, with completion counts and the current streak
+pub fn get_completed_tasks(range: CompletedRange) -> CompletedTasks {
+    CompletedLog::load().query(range)
+}
+
+/// An open task with just an id and project, for tests to fill in the rest
+#[cfg(test)]
+fn test_task(id: &str, project_id: &str) -> TickTickTask {
+    TickTickTask {
+        id: id.to_string(),
+        title: id.to_string(),
+        is_completed: false,
+        priority: 0,
+        due_date: None,
+        start_date: None,
+        project_id: project_id.to_string(),
+        project_name: None,
+        tags: Vec::new(),
+        created_time: String::new(),
+        modified_time: String::new(),
+        notes: None,
+        checklist: Vec::new(),
+        checklist_progress: None,
+        recurrence: None,
+        is_all_day: false,
+        time_zone: None,
+        due_day: None,
+        reminder_offsets: Vec::new(),
+        completed_time: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reminder_offsets_before_the_due_time() {
+        assert_eq!(reminder_offset("TRIGGER:-PT30M"), Some(-30));
+        assert_eq!(reminder_offset("TRIGGER:-P1DT2H"), Some(-26 * 60));
+        assert_eq!(reminder_offset("-P1W"), Some(-7 * 24 * 60));
+    }
+
+    #[test]
+    fn reminder_offsets_after_the_due_time() {
+        assert_eq!(reminder_offset("TRIGGER:P0DT9H0M0S"), Some(9 * 60));
+        assert_eq!(reminder_offset("TRIGGER:+PT15M"), Some(15));
+        assert_eq!(reminder_offset("TRIGGER:PT0S"), Some(0));
+    }
+
+    #[test]
+    fn reminder_offsets_reject_malformed_triggers() {
+        assert_eq!(reminder_offset("TRIGGER:30M"), None);
+        assert_eq!(reminder_offset("TRIGGER:-PTM"), None);
+        assert_eq!(reminder_offset("TRIGGER:-PT30X"), None);
+    }
+
+    fn server_task(modified_time: &str) -> ApiTask {
+        serde_json::from_value(serde_json::json!({
+            "id": "abc",
+            "projectId": "inbox",
+            "modifiedTime": modified_time,
+        }))
+        .unwrap()
+    }
+
+    fn pending(base_modified_time: Option<&str>, queued_at: &str) -> PendingTaskAction {
+        PendingTaskAction {
+            id: "1".to_string(),
+            action: TaskAction::Complete {
+                project_id: "inbox".to_string(),
+                task_id: "abc".to_string(),
+            },
+            queued_at: queued_at.to_string(),
+            base_modified_time: base_modified_time.map(str::to_string),
+            attempts: 0,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn edited_since_needs_a_later_remote_edit() {
+        let base = Some("2024-01-10T08:00:00.000+0000");
+        let queued_at = "2024-01-10T09:00:00+00:00";
+
+        // Unchanged since the change was queued
+        let server = server_task("2024-01-10T08:00:00.000+0000");
+        assert!(!edited_since(&server, &pending(base, queued_at)));
+
+        // Edited in TickTick after the change was queued
+        let server = server_task("2024-01-10T10:00:00.000+0000");
+        assert!(edited_since(&server, &pending(base, queued_at)));
+
+        // Edited before it was queued, by an earlier sync that isn't in the store
+        let server = server_task("2024-01-10T08:30:00.000+0000");
+        assert!(!edited_since(&server, &pending(base, queued_at)));
+
+        // Nothing to compare against, as for tasks created offline
+        let server = server_task("2024-01-10T10:00:00.000+0000");
+        assert!(!edited_since(&server, &pending(None, queued_at)));
+    }
+
+    #[test]
+    fn unavailable_responses_count_as_offline() {
+        for status in [
+            StatusCode::REQUEST_TIMEOUT,
+            StatusCode::TOO_MANY_REQUESTS,
+            StatusCode::INTERNAL_SERVER_ERROR,
+            StatusCode::SERVICE_UNAVAILABLE,
+        ] {
+            assert!(is_offline(&status_error(status, "")), "{}", status);
+        }
+        for status in [
+            StatusCode::BAD_REQUEST,
+            StatusCode::UNAUTHORIZED,
+            StatusCode::NOT_FOUND,
+        ] {
+            assert!(!is_offline(&status_error(status, "")), "{}", status);
+        }
+        assert!(is_offline(&format!(
+            "{}: connection refused",
+            REQUEST_FAILED
+        )));
+    }
+}
@@ -0,0 +1,201 @@
+use crate::api::{PendingTaskAction, TaskAction};
+use crate::config::get_config_dir;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// Prefix for ids of tasks created offline, until TickTick assigns a real one
+pub const LOCAL_ID_PREFIX: &str = "local-";
+
+/// Task changes waiting for TickTick to be reachable, oldest first, kept on
+/// disk so they survive a restart
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Outbox {
+    actions: Vec<PendingTaskAction>,
+    next_id: u64,
+}
+
+fn outbox_path() -> Result<PathBuf, String> {
+    get_config_dir()
+        .map(|p| p.join("ticktick_outbox.json"))
+        .map_err(|e| e.to_string())
+}
+
+impl Outbox {
+    pub fn load() -> Self {
+        outbox_path()
+            .ok()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let path = outbox_path()?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Failed to create config dir: {}", e))?;
+        }
+
+        let content = serde_json::to_string(self)
+            .map_err(|e| format!("Failed to serialize task outbox: {}", e))?;
+        fs::write(&path, content).map_err(|e| format!("Failed to write task outbox: {}", e))
+    }
+
+    pub fn actions(&self) -> Vec<PendingTaskAction> {
+        self.actions.clone()
+    }
+
+    /// Whether anything is still waiting to be sent, as opposed to failed
+    pub fn has_pending(&self) -> bool {
+        self.actions.iter().any(|a| a.error.is_none())
+    }
+
+    /// A fresh id for an action or a task created offline
+    pub fn next_id(&mut self) -> String {
+        self.next_id += 1;
+        self.next_id.to_string()
+    }
+
+    pub fn push(&mut self, action: TaskAction, base_modified_time: Option<String>) {
+        let id = self.next_id();
+        self.actions.push(PendingTaskAction {
+            id,
+            action,
+            queued_at: Local::now().to_rfc3339(),
+            base_modified_time,
+            attempts: 0,
+            error: None,
+        });
+    }
+
+    /// Id of the oldest action still waiting to be sent
+    pub fn first_pending(&self) -> Option<String> {
+        self.actions
+            .iter()
+            .find(|a| a.error.is_none())
+            .map(|a| a.id.clone())
+    }
+
+    /// Whether a completion of this task is still waiting to be sent
+    pub fn is_completing(&self, task_id: &str) -> bool {
+        self.actions.iter().any(|a| {
+            a.error.is_none()
+                && matches!(&a.action, TaskAction::Complete { task_id: id, .. } if id == task_id)
+        })
+    }
+
+    pub fn get_mut(&mut self, id: &str) -> Option<&mut PendingTaskAction> {
+        self.actions.iter_mut().find(|a| a.id == id)
+    }
+
+    /// Drop an action, returning whether it was queued
+    pub fn remove(&mut self, id: &str) -> bool {
+        let len = self.actions.len();
+        self.actions.retain(|a| a.id != id);
+        self.actions.len() != len
+    }
+
+    /// Point later actions on a task created offline at its real id. They
+    /// were made against the placeholder, so there's nothing to conflict with
+    pub fn replace_task_id(&mut self, local_id: &str, task_id: &str) {
+        for pending in &mut self.actions {
+            let id = match &mut pending.action {
+                TaskAction::Complete { task_id, .. } => task_id,
+                TaskAction::Update { update } => &mut update.id,
+                TaskAction::Create { .. } => continue,
+            };
+            if id == local_id {
+                *id = task_id.to_string();
+                pending.base_modified_time = None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{NewTickTickTask, TickTickTaskUpdate};
+
+    fn complete(task_id: &str) -> TaskAction {
+        TaskAction::Complete {
+            project_id: "inbox".to_string(),
+            task_id: task_id.to_string(),
+        }
+    }
+
+    fn update(task_id: &str) -> TaskAction {
+        TaskAction::Update {
+            update: TickTickTaskUpdate {
+                id: task_id.to_string(),
+                project_id: "inbox".to_string(),
+                title: Some("Renamed".to_string()),
+                due_date: None,
+                priority: None,
+            },
+        }
+    }
+
+    fn task_id(action: &TaskAction) -> &str {
+        match action {
+            TaskAction::Complete { task_id, .. } => task_id,
+            TaskAction::Update { update } => &update.id,
+            TaskAction::Create { local_id, .. } => local_id,
+        }
+    }
+
+    #[test]
+    fn replace_task_id_remaps_later_actions() {
+        let mut outbox = Outbox::default();
+        let local_id = format!("{}1", LOCAL_ID_PREFIX);
+        outbox.push(
+            TaskAction::Create {
+                task: NewTickTickTask {
+                    title: "New".to_string(),
+                    project_id: "inbox".to_string(),
+                    due_date: None,
+                    priority: 0,
+                },
+                local_id: local_id.clone(),
+            },
+            None,
+        );
+        outbox.push(update(&local_id), Some("placeholder".to_string()));
+        outbox.push(complete(&local_id), Some("placeholder".to_string()));
+        outbox.push(update("other"), Some("2024-01-01".to_string()));
+
+        outbox.replace_task_id(&local_id, "abc123");
+
+        let actions = outbox.actions();
+        let ids: Vec<&str> = actions.iter().map(|a| task_id(&a.action)).collect();
+        assert_eq!(ids, [local_id.as_str(), "abc123", "abc123", "other"]);
+
+        let bases: Vec<Option<&str>> = actions
+            .iter()
+            .map(|a| a.base_modified_time.as_deref())
+            .collect();
+        assert_eq!(bases, [None, None, None, Some("2024-01-01")]);
+    }
+
+    #[test]
+    fn failed_actions_are_not_pending() {
+        let mut outbox = Outbox::default();
+        outbox.push(complete("a"), None);
+        outbox.push(complete("b"), None);
+        assert!(outbox.is_completing("a"));
+
+        let first = outbox.first_pending().unwrap();
+        outbox.get_mut(&first).unwrap().error = Some("Rejected".to_string());
+
+        assert!(!outbox.is_completing("a"));
+        assert!(outbox.is_completing("b"));
+        assert_ne!(outbox.first_pending(), Some(first.clone()));
+        assert!(outbox.has_pending());
+
+        assert!(outbox.remove(&first));
+        assert!(!outbox.remove(&first));
+        assert_eq!(outbox.actions().len(), 1);
+    }
+}
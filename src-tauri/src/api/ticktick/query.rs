@@ -0,0 +1,468 @@
+use super::parse_ticktick_date;
+use crate::api::{TaskGroup, TaskQueryResult, TickTickData, TickTickTask};
+use crate::config::TaskGrouping;
+use chrono::{Duration, Local, NaiveDate};
+use std::cmp::Ordering;
+
+/// One term of a task query. Terms are separated by spaces and must all match;
+/// a leading `-` negates a term
+#[derive(Debug, Clone, PartialEq)]
+enum Filter {
+    /// `due:today`, `due:tomorrow`, `due:week` (the next 7 days) or `due:2024-01-31`
+    Due(DueRange),
+    /// `overdue`: due before today
+    Overdue,
+    /// `no-date`
+    NoDate,
+    /// `priority>=3`, also `>`, `<`, `<=` and `=`, with 0-5 or none/low/medium/high
+    Priority(Ordering, bool, i32),
+    /// `tag:home`
+    Tag(String),
+    /// `project:Work`, or `project:"Side projects"`
+    Project(String),
+    Not(Box<Filter>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DueRange {
+    Today,
+    Tomorrow,
+    Week,
+    On(NaiveDate),
+}
+
+/// Split on whitespace, keeping double-quoted values together
+fn tokenize(query: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+
+    for c in query.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+
+    tokens
+}
+
+fn parse_priority(value: &str) -> Option<i32> {
+    match value.to_lowercase().as_str() {
+        "none" => Some(0),
+        "low" => Some(1),
+        "medium" => Some(3),
+        "high" => Some(5),
+        number => number.parse().ok(),
+    }
+}
+
+fn parse_term(term: &str) -> Result<Filter, String> {
+    if let Some(negated) = term.strip_prefix('-') {
+        return Ok(Filter::Not(Box::new(parse_term(negated)?)));
+    }
+
+    let lower = term.to_lowercase();
+    match lower.as_str() {
+        "overdue" => return Ok(Filter::Overdue),
+        "no-date" => return Ok(Filter::NoDate),
+        _ => {}
+    }
+
+    if let Some(rest) = lower.strip_prefix("priority") {
+        // Longest operators first so ">=" isn't read as ">"
+        let (ordering, or_equal, value) = [
+            (">=", Ordering::Greater, true),
+            ("<=", Ordering::Less, true),
+            (">", Ordering::Greater, false),
+            ("<", Ordering::Less, false),
+            ("=", Ordering::Equal, true),
+            (":", Ordering::Equal, true),
+        ]
+        .iter()
+        .find_map(|(op, ordering, or_equal)| {
+            rest.strip_prefix(op)
+                .map(|value| (*ordering, *or_equal, value))
+        })
+        .ok_or_else(|| format!("Expected an operator after 'priority' in '{}'", term))?;
+
+        let priority =
+            parse_priority(value).ok_or_else(|| format!("Invalid priority in '{}'", term))?;
+        return Ok(Filter::Priority(ordering, or_equal, priority));
+    }
+
+    let (key, value) = term
+        .split_once(':')
+        .ok_or_else(|| format!("Unknown filter '{}'", term))?;
+
+    match key.to_lowercase().as_str() {
+        "due" => {
+            let range = match value.to_lowercase().as_str() {
+                "today" => DueRange::Today,
+                "tomorrow" => DueRange::Tomorrow,
+                "week" => DueRange::Week,
+                date => DueRange::On(
+                    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                        .map_err(|_| format!("Invalid due date in '{}'", term))?,
+                ),
+            };
+            Ok(Filter::Due(range))
+        }
+        "tag" => Ok(Filter::Tag(value.trim_start_matches('#').to_lowercase())),
+        "project" => Ok(Filter::Project(value.to_lowercase())),
+        _ => Err(format!("Unknown filter '{}'", term)),
+    }
+}
+
+fn parse(query: &str) -> Result<Vec<Filter>, String> {
+    tokenize(query).iter().map(|t| parse_term(t)).collect()
+}
+
+/// The local date a task is due, using the task's own day for all-day tasks
+fn due_date(task: &TickTickTask) -> Option<NaiveDate> {
+    if let Some(day) = &task.due_day {
+        return NaiveDate::parse_from_str(day, "%Y-%m-%d").ok();
+    }
+
+    parse_ticktick_date(task.due_date.as_deref()?).map(|d| d.with_timezone(&Local).date_naive())
+}
+
+fn matches(filter: &Filter, task: &TickTickTask, today: NaiveDate) -> bool {
+    let due = due_date(task);
+
+    match filter {
+        Filter::Due(range) => due.is_some_and(|d| match range {
+            DueRange::Today => d == today,
+            DueRange::Tomorrow => d == today + Duration::days(1),
+            DueRange::Week => d >= today && d < today + Duration::days(7),
+            DueRange::On(date) => d == *date,
+        }),
+        Filter::Overdue => due.is_some_and(|d| d < today),
+        Filter::NoDate => due.is_none(),
+        Filter::Priority(ordering, or_equal, value) => {
+            let cmp = task.priority.cmp(value);
+            cmp == *ordering || (*or_equal && cmp == Ordering::Equal)
+        }
+        Filter::Tag(tag) => task.tags.iter().any(|t| t.to_lowercase() == *tag),
+        Filter::Project(project) => task
+            .project_name
+            .as_ref()
+            .is_some_and(|name| name.to_lowercase() == *project),
+        Filter::Not(inner) => !matches(inner, task, today),
+    }
+}
+
+/// Higher priority first, then earliest due, undated last
+fn compare_tasks(a: &TickTickTask, b: &TickTickTask) -> Ordering {
+    b.priority
+        .cmp(&a.priority)
+        .then_with(|| match (due_date(a), due_date(b)) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        })
+        .then_with(|| a.title.cmp(&b.title))
+}
+
+fn due_bucket(task: &TickTickTask, today: NaiveDate) -> (u8, &'static str) {
+    match due_date(task) {
+        Some(d) if d < today => (0, "Overdue"),
+        Some(d) if d == today => (1, "Today"),
+        Some(d) if d == today + Duration::days(1) => (2, "Tomorrow"),
+        Some(d) if d < today + Duration::days(7) => (3, "This week"),
+        Some(_) => (4, "Later"),
+        None => (5, "No date"),
+    }
+}
+
+fn priority_label(priority: i32) -> &'static str {
+    match priority {
+        5 => "High",
+        3 => "Medium",
+        1 => "Low",
+        _ => "None",
+    }
+}
+
+/// Key, label and sort position of the group a task belongs to
+fn group_of(
+    task: &TickTickTask,
+    grouping: TaskGrouping,
+    data: &TickTickData,
+    today: NaiveDate,
+) -> (String, String, i64) {
+    match grouping {
+        TaskGrouping::None => ("all".to_string(), "All tasks".to_string(), 0),
+        TaskGrouping::Project => {
+            let sort_order = data
+                .projects
+                .iter()
+                .find(|p| p.id == task.project_id)
+                .map_or(i64::MAX, |p| p.sort_order);
+            (
+                task.project_id.clone(),
+                task.project_name
+                    .clone()
+                    .unwrap_or_else(|| "Inbox".to_string()),
+                sort_order,
+            )
+        }
+        TaskGrouping::Due => {
+            let (order, label) = due_bucket(task, today);
+            (
+                label.to_lowercase().replace(' ', "-"),
+                label.to_string(),
+                order as i64,
+            )
+        }
+        TaskGrouping::Priority => (
+            task.priority.to_string(),
+            priority_label(task.priority).to_string(),
+            -(task.priority as i64),
+        ),
+    }
+}
+
+/// Filter and group open tasks with a query such as `due:today priority>=3`
+pub fn query_tasks(
+    data: &TickTickData,
+    query: &str,
+    grouping: TaskGrouping,
+) -> Result<TaskQueryResult, String> {
+    let filters = parse(query)?;
+    let today = Local::now().date_naive();
+
+    let mut tasks: Vec<&TickTickTask> = data
+        .tasks
+        .iter()
+        .filter(|task| filters.iter().all(|f| matches(f, task, today)))
+        .collect();
+    tasks.sort_by(|a, b| compare_tasks(a, b));
+
+    let mut groups: Vec<(i64, TaskGroup)> = Vec::new();
+    for task in &tasks {
+        let (key, label, order) = group_of(task, grouping, data, today);
+        match groups.iter_mut().find(|(_, g)| g.key == key) {
+            Some((_, group)) => group.tasks.push((*task).clone()),
+            None => groups.push((
+                order,
+                TaskGroup {
+                    key,
+                    label,
+                    tasks: vec![(*task).clone()],
+                },
+            )),
+        }
+    }
+    groups.sort_by_key(|(order, _)| *order);
+
+    Ok(TaskQueryResult {
+        query: query.to_string(),
+        group_by: grouping,
+        total: tasks.len(),
+        groups: groups.into_iter().map(|(_, group)| group).collect(),
+        last_updated: data.last_updated.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::ticktick::test_task;
+    use crate::api::{TaskChanges, TickTickProject};
+    use chrono::TimeZone;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    /// Noon on `day` in TickTick's date format, so it's that day locally
+    fn due(day: &str) -> Option<String> {
+        let noon = date(day).and_hms_opt(12, 0, 0)?;
+        let time = Local.from_local_datetime(&noon).earliest()?;
+        Some(time.format("%Y-%m-%dT%H:%M:%S%.3f%z").to_string())
+    }
+
+    fn project(id: &str, name: &str, sort_order: i64) -> TickTickProject {
+        TickTickProject {
+            id: id.to_string(),
+            name: name.to_string(),
+            color: None,
+            sort_order,
+        }
+    }
+
+    #[test]
+    fn parses_priority_operators() {
+        assert_eq!(
+            parse_term("priority>=3"),
+            Ok(Filter::Priority(Ordering::Greater, true, 3))
+        );
+        assert_eq!(
+            parse_term("priority<=low"),
+            Ok(Filter::Priority(Ordering::Less, true, 1))
+        );
+        assert_eq!(
+            parse_term("priority>1"),
+            Ok(Filter::Priority(Ordering::Greater, false, 1))
+        );
+        assert_eq!(
+            parse_term("priority<high"),
+            Ok(Filter::Priority(Ordering::Less, false, 5))
+        );
+        assert_eq!(
+            parse_term("priority=none"),
+            Ok(Filter::Priority(Ordering::Equal, true, 0))
+        );
+        assert_eq!(
+            parse_term("PRIORITY:Medium"),
+            Ok(Filter::Priority(Ordering::Equal, true, 3))
+        );
+        assert!(parse_term("priority").is_err());
+        assert!(parse_term("priority>=urgent").is_err());
+    }
+
+    #[test]
+    fn parses_keyed_terms() {
+        assert_eq!(parse_term("due:today"), Ok(Filter::Due(DueRange::Today)));
+        assert_eq!(parse_term("due:Week"), Ok(Filter::Due(DueRange::Week)));
+        assert_eq!(
+            parse_term("due:2024-01-31"),
+            Ok(Filter::Due(DueRange::On(date("2024-01-31"))))
+        );
+        assert_eq!(parse_term("tag:#Home"), Ok(Filter::Tag("home".to_string())));
+        assert_eq!(parse_term("overdue"), Ok(Filter::Overdue));
+        assert_eq!(parse_term("no-date"), Ok(Filter::NoDate));
+        assert!(parse_term("due:someday").is_err());
+        assert!(parse_term("colour:red").is_err());
+        assert!(parse_term("urgent").is_err());
+    }
+
+    #[test]
+    fn negates_terms() {
+        assert_eq!(
+            parse_term("-tag:errand"),
+            Ok(Filter::Not(Box::new(Filter::Tag("errand".to_string()))))
+        );
+        assert_eq!(
+            parse_term("-no-date"),
+            Ok(Filter::Not(Box::new(Filter::NoDate)))
+        );
+    }
+
+    #[test]
+    fn keeps_quoted_values_together() {
+        assert_eq!(
+            tokenize(r#"project:"Side projects"  due:today"#),
+            ["project:Side projects", "due:today"]
+        );
+        assert_eq!(
+            parse(r#"-project:"Side Projects""#),
+            Ok(vec![Filter::Not(Box::new(Filter::Project(
+                "side projects".to_string()
+            )))])
+        );
+    }
+
+    #[test]
+    fn buckets_by_due_date() {
+        let today = date("2024-01-10");
+        let bucket = |day: Option<&str>| {
+            let task = TickTickTask {
+                due_date: day.and_then(due),
+                ..test_task("t", "work")
+            };
+            due_bucket(&task, today).1
+        };
+
+        assert_eq!(bucket(Some("2024-01-09")), "Overdue");
+        assert_eq!(bucket(Some("2024-01-10")), "Today");
+        assert_eq!(bucket(Some("2024-01-11")), "Tomorrow");
+        assert_eq!(bucket(Some("2024-01-16")), "This week");
+        assert_eq!(bucket(Some("2024-01-17")), "Later");
+        assert_eq!(bucket(None), "No date");
+
+        let due_on = |day: &str| TickTickTask {
+            due_date: due(day),
+            ..test_task("t", "work")
+        };
+        let week = Filter::Due(DueRange::Week);
+        assert!(matches(&week, &due_on("2024-01-16"), today));
+        assert!(!matches(&week, &due_on("2024-01-17"), today));
+        assert!(matches(&Filter::Overdue, &due_on("2024-01-09"), today));
+        assert!(matches(&Filter::NoDate, &test_task("t", "work"), today));
+    }
+
+    #[test]
+    fn all_day_tasks_use_their_own_day() {
+        // Midnight in Auckland is the previous day in UTC and most of the world
+        let task = TickTickTask {
+            due_date: Some("2024-01-09T11:00:00.000+0000".to_string()),
+            is_all_day: true,
+            time_zone: Some("Pacific/Auckland".to_string()),
+            due_day: Some("2024-01-10".to_string()),
+            ..test_task("t", "work")
+        };
+
+        assert_eq!(due_date(&task), Some(date("2024-01-10")));
+        assert_eq!(due_bucket(&task, date("2024-01-10")).1, "Today");
+    }
+
+    #[test]
+    fn filters_and_groups_tasks() {
+        let in_project = |id: &str, priority: i32, project: &str| TickTickTask {
+            priority,
+            project_name: Some(project.to_string()),
+            ..test_task(id, &project.to_lowercase())
+        };
+        let data = TickTickData {
+            tasks: vec![
+                in_project("groceries", 1, "Home"),
+                in_project("report", 5, "Work"),
+                in_project("review", 3, "Work"),
+                in_project("laundry", 0, "Home"),
+            ],
+            projects: vec![project("work", "Work", 1), project("home", "Home", 2)],
+            failed_projects: Vec::new(),
+            changes: TaskChanges::default(),
+            pending_actions: Vec::new(),
+            offline: false,
+            last_updated: String::new(),
+        };
+
+        let result = query_tasks(&data, "priority>=1", TaskGrouping::Project).unwrap();
+        assert_eq!(result.total, 3);
+        let groups: Vec<(&str, Vec<&str>)> = result
+            .groups
+            .iter()
+            .map(|g| {
+                (
+                    g.label.as_str(),
+                    g.tasks.iter().map(|t| t.id.as_str()).collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            groups,
+            [
+                ("Work", vec!["report", "review"]),
+                ("Home", vec!["groceries"])
+            ]
+        );
+
+        let result = query_tasks(&data, "-project:work", TaskGrouping::Priority).unwrap();
+        let labels: Vec<&str> = result.groups.iter().map(|g| g.label.as_str()).collect();
+        assert_eq!(labels, ["Low", "None"]);
+
+        assert!(query_tasks(&data, "priority>>3", TaskGrouping::None).is_err());
+    }
+}
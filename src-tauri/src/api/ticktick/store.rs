@@ -0,0 +1,191 @@
+use crate::api::{ProjectFailure, TaskChanges, TickTickProject, TickTickTask};
+use crate::config::get_config_dir;
+use chrono::{DateTime, Duration, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+
+/// Open tasks as of the last sync, kept on disk so a refresh can report what
+/// changed and recent refreshes can skip the API entirely
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskStore {
+    pub last_sync: Option<String>,
+    pub projects: Vec<TickTickProject>,
+    pub failed_projects: Vec<ProjectFailure>,
+    tasks: BTreeMap<String, TickTickTask>,
+}
+
+fn store_path() -> Result<PathBuf, String> {
+    get_config_dir()
+        .map(|p| p.join("ticktick_tasks.json"))
+        .map_err(|e| e.to_string())
+}
+
+impl TaskStore {
+    /// The saved store, or an empty one if it's missing or unreadable
+    pub fn load() -> Self {
+        store_path()
+            .ok()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let path = store_path()?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Failed to create config dir: {}", e))?;
+        }
+
+        let content = serde_json::to_string(self)
+            .map_err(|e| format!("Failed to serialize task store: {}", e))?;
+        fs::write(&path, content).map_err(|e| format!("Failed to write task store: {}", e))
+    }
+
+    /// Whether the last sync was less than `interval` ago
+    pub fn synced_within(&self, interval: Duration) -> bool {
+        self.last_sync
+            .as_deref()
+            .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+            .is_some_and(|last| Local::now().signed_duration_since(last) < interval)
+    }
+
+    pub fn tasks(&self) -> Vec<TickTickTask> {
+        self.tasks.values().cloned().collect()
+    }
+
+    /// Merge a fresh fetch into the store. Only tasks whose contents changed
+    /// (usually a new `modified_time`) count as updated, and tasks in projects
+    /// that failed to load keep their last known state rather than being removed
+    pub fn merge(
+        &mut self,
+        projects: Vec<TickTickProject>,
+        tasks: Vec<TickTickTask>,
+        failed_projects: Vec<ProjectFailure>,
+    ) -> TaskChanges {
+        let failed: HashSet<&str> = failed_projects
+            .iter()
+            .map(|f| f.project_id.as_str())
+            .collect();
+        let fetched: HashSet<&str> = tasks.iter().map(|t| t.id.as_str()).collect();
+
+        let mut changes = TaskChanges::default();
+
+        let removed: Vec<String> = self
+            .tasks
+            .values()
+            .filter(|t| !fetched.contains(t.id.as_str()) && !failed.contains(t.project_id.as_str()))
+            .map(|t| t.id.clone())
+            .collect();
+        for id in removed {
+            self.tasks.remove(&id);
+            changes.removed.push(id);
+        }
+
+        for task in tasks {
+            match self.tasks.get(&task.id) {
+                None => changes.added.push(task.clone()),
+                Some(existing) if *existing != task => changes.updated.push(task.clone()),
+                Some(_) => continue,
+            }
+            self.tasks.insert(task.id.clone(), task);
+        }
+
+        self.projects = projects;
+        self.failed_projects = failed_projects;
+        self.last_sync = Some(Local::now().to_rfc3339());
+
+        changes
+    }
+
+    /// Record a task created or edited from the dashboard
+    pub fn upsert(&mut self, mut task: TickTickTask) {
+        if task.project_name.is_none() {
+            task.project_name = self
+                .projects
+                .iter()
+                .find(|p| p.id == task.project_id)
+                .map(|p| p.name.clone());
+        }
+        self.tasks.insert(task.id.clone(), task);
+    }
+
+    pub fn get(&self, task_id: &str) -> Option<&TickTickTask> {
+        self.tasks.get(task_id)
+    }
+
+    /// Drop a task completed from the dashboard, returning it
+    pub fn remove(&mut self, task_id: &str) -> Option<TickTickTask> {
+        self.tasks.remove(task_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::ticktick::test_task;
+
+    fn edited(id: &str, project_id: &str, modified_time: &str) -> TickTickTask {
+        TickTickTask {
+            modified_time: modified_time.to_string(),
+            ..test_task(id, project_id)
+        }
+    }
+
+    fn ids(tasks: &[TickTickTask]) -> Vec<&str> {
+        tasks.iter().map(|t| t.id.as_str()).collect()
+    }
+
+    #[test]
+    fn merge_reports_added_updated_and_removed() {
+        let mut store = TaskStore::default();
+        let first = store.merge(
+            Vec::new(),
+            vec![
+                edited("a", "inbox", "1"),
+                edited("b", "inbox", "1"),
+                edited("c", "work", "1"),
+            ],
+            Vec::new(),
+        );
+        assert_eq!(ids(&first.added), ["a", "b", "c"]);
+        assert!(store.last_sync.is_some());
+
+        let second = store.merge(
+            Vec::new(),
+            vec![
+                edited("a", "inbox", "1"),
+                edited("b", "inbox", "2"),
+                edited("d", "work", "1"),
+            ],
+            Vec::new(),
+        );
+        assert_eq!(ids(&second.added), ["d"]);
+        assert_eq!(ids(&second.updated), ["b"]);
+        assert_eq!(second.removed, ["c"]);
+        assert_eq!(ids(&store.tasks()), ["a", "b", "d"]);
+    }
+
+    #[test]
+    fn merge_keeps_tasks_from_failed_projects() {
+        let mut store = TaskStore::default();
+        store.merge(
+            Vec::new(),
+            vec![test_task("a", "inbox"), test_task("w", "work")],
+            Vec::new(),
+        );
+
+        let failure = ProjectFailure {
+            project_id: "work".to_string(),
+            project_name: "Work".to_string(),
+            error: "TickTick API error: 500".to_string(),
+        };
+        let changes = store.merge(Vec::new(), vec![test_task("a", "inbox")], vec![failure]);
+
+        assert!(changes.removed.is_empty());
+        assert_eq!(ids(&store.tasks()), ["a", "w"]);
+        assert_eq!(store.failed_projects.len(), 1);
+    }
+}
@@ -0,0 +1,39 @@
+use super::WeatherProvider;
+use crate::api::WeatherData;
+use crate::config::{get_config_dir, WeatherConfig};
+use chrono::Local;
+use reqwest::Client;
+use std::fs;
+use std::path::PathBuf;
+
+/// Reads a serialized `WeatherData` from disk, for working on the widget offline
+pub struct FixtureProvider;
+
+fn fixture_path(config: &WeatherConfig) -> Result<PathBuf, String> {
+    match &config.fixture_path {
+        Some(path) => Ok(PathBuf::from(path)),
+        None => get_config_dir()
+            .map(|p| p.join("weather.fixture.json"))
+            .map_err(|e| e.to_string()),
+    }
+}
+
+impl WeatherProvider for FixtureProvider {
+    async fn fetch_weather(
+        &self,
+        _client: &Client,
+        config: &WeatherConfig,
+    ) -> Result<WeatherData, String> {
+        let path = fixture_path(config)?;
+
+        let content = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read weather fixture {}: {}", path.display(), e))?;
+
+        let mut data: WeatherData = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse weather fixture {}: {}", path.display(), e))?;
+
+        data.last_updated = Local::now().to_rfc3339();
+
+        Ok(data)
+    }
+}
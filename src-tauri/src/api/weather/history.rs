@@ -0,0 +1,163 @@
+use super::{open_meteo, timezone};
+use crate::api::{DailyWeatherSummary, PeriodSummary, WeatherHistory};
+use crate::config::{get_config_dir, WeatherConfig};
+use chrono::{Duration, NaiveDate, Utc};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Open-Meteo's forecast API serves at most 92 past days
+const MAX_HISTORY_DAYS: u32 = 92;
+
+/// Daily summaries persisted between runs, keyed by date
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct HistoryStore {
+    latitude: f64,
+    longitude: f64,
+    days: BTreeMap<String, DailyWeatherSummary>,
+}
+
+fn history_path() -> Result<PathBuf, String> {
+    get_config_dir()
+        .map(|p| p.join("weather_history.json"))
+        .map_err(|e| e.to_string())
+}
+
+fn load_store(config: &WeatherConfig) -> HistoryStore {
+    let store = history_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str::<HistoryStore>(&content).ok());
+
+    match store {
+        // History for a different location is useless, start over
+        Some(store) if store.latitude == config.latitude && store.longitude == config.longitude => {
+            store
+        }
+        _ => HistoryStore {
+            latitude: config.latitude,
+            longitude: config.longitude,
+            days: BTreeMap::new(),
+        },
+    }
+}
+
+fn save_store(store: &HistoryStore) -> Result<(), String> {
+    let path = history_path()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create config dir: {}", e))?;
+    }
+
+    let content = serde_json::to_string(store)
+        .map_err(|e| format!("Failed to serialize weather history: {}", e))?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write weather history: {}", e))
+}
+
+fn summarize(days: &[&DailyWeatherSummary], start: NaiveDate, end: NaiveDate) -> PeriodSummary {
+    let average = |values: Vec<f64>| {
+        if values.is_empty() {
+            None
+        } else {
+            let avg = values.iter().sum::<f64>() / values.len() as f64;
+            Some((avg * 10.0).round() / 10.0)
+        }
+    };
+
+    let total_precipitation: f64 = days.iter().map(|d| d.precipitation).sum();
+
+    PeriodSummary {
+        start_date: start.format("%Y-%m-%d").to_string(),
+        end_date: end.format("%Y-%m-%d").to_string(),
+        avg_high: average(days.iter().map(|d| d.high).collect()),
+        avg_low: average(days.iter().map(|d| d.low).collect()),
+        total_precipitation: (total_precipitation * 10.0).round() / 10.0,
+        wet_days: days.iter().filter(|d| d.precipitation >= 1.0).count() as u32,
+    }
+}
+
+fn days_between(
+    store: &HistoryStore,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Vec<&DailyWeatherSummary> {
+    let start = start.format("%Y-%m-%d").to_string();
+    let end = end.format("%Y-%m-%d").to_string();
+    store.days.range(start..=end).map(|(_, day)| day).collect()
+}
+
+/// Daily history for the last `days` days (including today), backfilling any
+/// gaps from Open-Meteo and persisting the result
+pub async fn get_weather_history(
+    config: &WeatherConfig,
+    days: u32,
+) -> Result<WeatherHistory, String> {
+    let days = days.clamp(1, MAX_HISTORY_DAYS);
+    // Always keep two weeks around for the week-over-week comparison
+    let past_days = days.max(14);
+
+    let today = Utc::now().with_timezone(&timezone(config)).date_naive();
+    let earliest = today - Duration::days(past_days as i64 - 1);
+
+    let mut store = load_store(config);
+
+    let missing = (0..past_days as i64)
+        .map(|offset| {
+            (earliest + Duration::days(offset))
+                .format("%Y-%m-%d")
+                .to_string()
+        })
+        .any(|date| !store.days.contains_key(&date));
+
+    // Today is still in progress, so it's refreshed on every call
+    let client = Client::new();
+    let fetch_days = if missing { past_days } else { 0 };
+    match open_meteo::fetch_daily_history(&client, config, fetch_days).await {
+        Ok(summaries) => {
+            for summary in summaries {
+                store.days.insert(summary.date.clone(), summary);
+            }
+
+            // Drop anything Open-Meteo could no longer backfill anyway
+            let cutoff = (today - Duration::days(MAX_HISTORY_DAYS as i64))
+                .format("%Y-%m-%d")
+                .to_string();
+            store.days.retain(|date, _| *date >= cutoff);
+
+            if let Err(e) = save_store(&store) {
+                log::warn!("Weather: {}", e);
+            }
+        }
+        // Serve what we already have when offline
+        Err(e) if !store.days.is_empty() => log::warn!("Weather: {}", e),
+        Err(e) => return Err(e),
+    }
+
+    let this_week_start = today - Duration::days(6);
+    let last_week_end = this_week_start - Duration::days(1);
+    let last_week_start = last_week_end - Duration::days(6);
+
+    let this_week = summarize(
+        &days_between(&store, this_week_start, today),
+        this_week_start,
+        today,
+    );
+    let last_week = summarize(
+        &days_between(&store, last_week_start, last_week_end),
+        last_week_start,
+        last_week_end,
+    );
+
+    let range_start = today - Duration::days(days as i64 - 1);
+    let days = days_between(&store, range_start, today)
+        .into_iter()
+        .cloned()
+        .collect();
+
+    Ok(WeatherHistory {
+        days,
+        this_week,
+        last_week,
+    })
+}
@@ -0,0 +1,290 @@
+use super::{build_location, timezone, WeatherProvider};
+use crate::api::{
+    ConditionCategory, HourlyWeather, Intensity, PrecipitationType, WeatherCondition, WeatherData,
+};
+use crate::config::WeatherConfig;
+use chrono::{DateTime, FixedOffset, Local, Timelike, Utc};
+use chrono_tz::Tz;
+use reqwest::Client;
+use serde::Deserialize;
+
+const MET_FORECAST_URL: &str = "https://api.met.no/weatherapi/locationforecast/2.0/compact";
+const MET_SUNRISE_URL: &str = "https://api.met.no/weatherapi/sunrise/3.0/sun";
+
+// MET Norway's terms of service require an identifying User-Agent
+const MET_USER_AGENT: &str = concat!(
+    "inkdash/",
+    env!("CARGO_PKG_VERSION"),
+    " github.com/vinothpandian/inkdash"
+);
+
+/// MET Norway Locationforecast API (no API key required)
+pub struct MetNorway;
+
+#[derive(Debug, Deserialize)]
+struct ForecastResponse {
+    properties: ForecastProperties,
+}
+
+#[derive(Debug, Deserialize)]
+struct ForecastProperties {
+    timeseries: Vec<TimeStep>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TimeStep {
+    time: String,
+    data: TimeStepData,
+}
+
+#[derive(Debug, Deserialize)]
+struct TimeStepData {
+    instant: InstantData,
+    next_1_hours: Option<NextHours>,
+    next_6_hours: Option<NextHours>,
+}
+
+#[derive(Debug, Deserialize)]
+struct InstantData {
+    details: InstantDetails,
+}
+
+#[derive(Debug, Deserialize)]
+struct InstantDetails {
+    air_temperature: f64,
+    #[serde(default)]
+    relative_humidity: f64,
+    #[serde(default)]
+    wind_speed: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct NextHours {
+    summary: Summary,
+}
+
+#[derive(Debug, Deserialize)]
+struct Summary {
+    symbol_code: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SunResponse {
+    properties: SunProperties,
+}
+
+#[derive(Debug, Deserialize)]
+struct SunProperties {
+    sunrise: Option<SunEvent>,
+    sunset: Option<SunEvent>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SunEvent {
+    time: String,
+}
+
+/// Map a MET symbol code (e.g. "lightrainshowers_day") to a structured condition.
+/// Symbols without a day/night variant fall back to `is_day`
+fn map_symbol_code(symbol: &str, is_day: bool) -> WeatherCondition {
+    let (base, variant) = symbol.split_once('_').unwrap_or((symbol, ""));
+    let is_day = match variant {
+        "night" => false,
+        "day" | "polartwilight" => true,
+        _ => is_day,
+    };
+
+    let intensity = if base.starts_with("light") {
+        Intensity::Light
+    } else if base.starts_with("heavy") {
+        Intensity::Heavy
+    } else {
+        Intensity::Moderate
+    };
+
+    // MET spells some codes "lightssleet", so match on substrings
+    let precipitation = if base.contains("snow") {
+        Some(PrecipitationType::Snow)
+    } else if base.contains("sleet") {
+        Some(PrecipitationType::Freezing)
+    } else if base.contains("rain") {
+        Some(PrecipitationType::Rain)
+    } else {
+        None
+    };
+
+    let (category, intensity, precipitation) = match base {
+        "clearsky" => (ConditionCategory::Clear, None, None),
+        "fair" | "partlycloudy" => (ConditionCategory::PartlyCloudy, None, None),
+        "fog" => (ConditionCategory::Fog, None, None),
+        _ if base.contains("thunder") => (
+            ConditionCategory::Thunderstorm,
+            Some(intensity),
+            precipitation.or(Some(PrecipitationType::Rain)),
+        ),
+        _ if precipitation == Some(PrecipitationType::Snow) => {
+            (ConditionCategory::Snow, Some(intensity), precipitation)
+        }
+        _ if precipitation.is_some() => (ConditionCategory::Rain, Some(intensity), precipitation),
+        _ => (ConditionCategory::Cloudy, None, None),
+    };
+
+    WeatherCondition::new(category, intensity, precipitation, is_day)
+}
+
+/// MET sunrise times omit seconds ("2026-01-15T07:48-05:00")
+fn parse_sun_time(time: &str) -> Option<DateTime<FixedOffset>> {
+    DateTime::parse_from_rfc3339(time)
+        .or_else(|_| DateTime::parse_from_str(time, "%Y-%m-%dT%H:%M%:z"))
+        .ok()
+}
+
+/// Whether `time` falls between sunrise and sunset, or 6am to 6pm in `time`'s
+/// timezone when the sun times are missing
+fn is_daytime(time: &DateTime<Tz>, sunrise: &str, sunset: &str) -> bool {
+    match (parse_sun_time(sunrise), parse_sun_time(sunset)) {
+        (Some(rise), Some(set)) => *time >= rise && *time < set,
+        _ => (6..18).contains(&time.hour()),
+    }
+}
+
+fn symbol_for(step: &TimeStep) -> &str {
+    step.data
+        .next_1_hours
+        .as_ref()
+        .or(step.data.next_6_hours.as_ref())
+        .map(|n| n.summary.symbol_code.as_str())
+        .unwrap_or("cloudy")
+}
+
+/// Wind chill (Environment Canada formula) when it's cold and windy, otherwise the air temperature
+fn apparent_temperature(temp_c: f64, wind_kmh: f64) -> f64 {
+    if temp_c <= 10.0 && wind_kmh > 4.8 {
+        let v = wind_kmh.powf(0.16);
+        13.12 + 0.6215 * temp_c - 11.37 * v + 0.3965 * temp_c * v
+    } else {
+        temp_c
+    }
+}
+
+async fn fetch_sun_times(
+    client: &Client,
+    config: &WeatherConfig,
+    tz: &Tz,
+) -> Result<(String, String), String> {
+    let now = Utc::now().with_timezone(tz);
+    let url = format!(
+        "{}?lat={:.4}&lon={:.4}&date={}&offset={}",
+        MET_SUNRISE_URL,
+        config.latitude,
+        config.longitude,
+        now.format("%Y-%m-%d"),
+        urlencoding::encode(&now.format("%:z").to_string())
+    );
+
+    let response = client
+        .get(&url)
+        .header("User-Agent", MET_USER_AGENT)
+        .send()
+        .await
+        .map_err(|e| format!("MET sunrise API request failed: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("MET sunrise API error: {}", response.status()));
+    }
+
+    let data: SunResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse MET sunrise response: {}", e))?;
+
+    Ok((
+        data.properties.sunrise.map(|s| s.time).unwrap_or_default(),
+        data.properties.sunset.map(|s| s.time).unwrap_or_default(),
+    ))
+}
+
+impl WeatherProvider for MetNorway {
+    async fn fetch_weather(
+        &self,
+        client: &Client,
+        config: &WeatherConfig,
+    ) -> Result<WeatherData, String> {
+        // MET asks clients to use at most 4 decimals for coordinates
+        let url = format!(
+            "{}?lat={:.4}&lon={:.4}",
+            MET_FORECAST_URL, config.latitude, config.longitude
+        );
+
+        let response = client
+            .get(&url)
+            .header("User-Agent", MET_USER_AGENT)
+            .send()
+            .await
+            .map_err(|e| format!("MET weather API request failed: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("MET weather API error: {}", response.status()));
+        }
+
+        let data: ForecastResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse MET weather response: {}", e))?;
+
+        let current = data
+            .properties
+            .timeseries
+            .first()
+            .ok_or_else(|| "MET weather response has no forecast data".to_string())?;
+
+        let tz = timezone(config);
+        let now = Utc::now().with_timezone(&tz);
+        let (sunrise, sunset) = match fetch_sun_times(client, config, &tz).await {
+            Ok(times) => times,
+            Err(e) => {
+                log::warn!("Weather: {}", e);
+                (String::new(), String::new())
+            }
+        };
+
+        let details = &current.data.instant.details;
+        let wind_speed = details.wind_speed * 3.6; // m/s -> km/h
+        let condition = map_symbol_code(symbol_for(current), is_daytime(&now, &sunrise, &sunset));
+
+        // Timeseries starts at the current hour, so hours of the configured day
+        // that have already passed are left out
+        let today = now.date_naive();
+        let hourly_forecast: Vec<HourlyWeather> = data
+            .properties
+            .timeseries
+            .iter()
+            .filter_map(|step| {
+                let time = DateTime::parse_from_rfc3339(&step.time).ok()?;
+                Some((time.with_timezone(&tz), step))
+            })
+            .filter(|(time, _)| time.date_naive() == today)
+            .map(|(time, step)| HourlyWeather {
+                hour: time.hour() as i32,
+                temperature: step.data.instant.details.air_temperature.round() as i32,
+                condition: map_symbol_code(symbol_for(step), is_daytime(&time, &sunrise, &sunset)),
+            })
+            .collect();
+
+        Ok(WeatherData {
+            location: build_location(config),
+            condition,
+            temperature: details.air_temperature.round() as i32,
+            feels_like: apparent_temperature(details.air_temperature, wind_speed).round() as i32,
+            humidity: details.relative_humidity.round() as i32,
+            wind_speed: wind_speed.round() as i32,
+            unit: "celsius".to_string(),
+            sunrise,
+            sunset,
+            hourly_forecast,
+            air_quality: None,
+            measured: Vec::new(),
+            last_updated: Local::now().to_rfc3339(),
+        })
+    }
+}
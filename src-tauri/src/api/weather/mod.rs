@@ -0,0 +1,203 @@
+mod fixture;
+mod history;
+mod met_norway;
+mod open_meteo;
+
+use super::{
+    AirQuality, ConditionCategory, Intensity, PrecipitationType, WeatherCondition, WeatherData,
+    WeatherLocation,
+};
+use crate::config::{WeatherConfig, WeatherProviderKind};
+use chrono_tz::Tz;
+use reqwest::Client;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+pub use fixture::FixtureProvider;
+pub use history::get_weather_history;
+pub use met_norway::MetNorway;
+pub use open_meteo::OpenMeteo;
+
+// Responses are reused for a while so the widget and the air quality command
+// don't each hit the provider on every refresh
+const CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// A source of forecast data, mapped into the shared `WeatherData`
+pub trait WeatherProvider {
+    async fn fetch_weather(
+        &self,
+        client: &Client,
+        config: &WeatherConfig,
+    ) -> Result<WeatherData, String>;
+}
+
+impl WeatherCondition {
+    pub fn new(
+        category: ConditionCategory,
+        intensity: Option<Intensity>,
+        precipitation: Option<PrecipitationType>,
+        is_day: bool,
+    ) -> Self {
+        let description = describe(category, intensity, precipitation);
+        Self {
+            category,
+            intensity,
+            precipitation,
+            is_day,
+            description,
+        }
+    }
+}
+
+fn describe(
+    category: ConditionCategory,
+    intensity: Option<Intensity>,
+    precipitation: Option<PrecipitationType>,
+) -> String {
+    let noun = match category {
+        ConditionCategory::Clear => return "Clear".to_string(),
+        ConditionCategory::PartlyCloudy => return "Partly cloudy".to_string(),
+        ConditionCategory::Cloudy => return "Cloudy".to_string(),
+        ConditionCategory::Fog => return "Fog".to_string(),
+        ConditionCategory::Thunderstorm => {
+            let base = match intensity {
+                Some(Intensity::Heavy) => "Heavy thunderstorm",
+                _ => "Thunderstorm",
+            };
+            return match precipitation {
+                Some(PrecipitationType::Hail) => format!("{} with hail", base),
+                _ => base.to_string(),
+            };
+        }
+        ConditionCategory::Drizzle => "drizzle",
+        ConditionCategory::Rain => "rain",
+        ConditionCategory::Snow => "snow",
+    };
+
+    let mut words = Vec::new();
+    match intensity {
+        Some(Intensity::Light) => words.push("light"),
+        Some(Intensity::Heavy) => words.push("heavy"),
+        _ => {}
+    }
+    if precipitation == Some(PrecipitationType::Freezing) {
+        words.push("freezing");
+    }
+    words.push(noun);
+
+    let text = words.join(" ");
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+        None => text,
+    }
+}
+
+struct CachedResponse<T> {
+    key: String,
+    fetched_at: Instant,
+    data: T,
+}
+
+static WEATHER_CACHE: Mutex<Option<CachedResponse<WeatherData>>> = Mutex::new(None);
+static AIR_QUALITY_CACHE: Mutex<Option<CachedResponse<AirQuality>>> = Mutex::new(None);
+
+fn cache_key(config: &WeatherConfig) -> String {
+    format!(
+        "{:?}:{},{}:{}",
+        config.provider,
+        config.latitude,
+        config.longitude,
+        timezone(config)
+    )
+}
+
+fn get_cached<T: Clone>(
+    cache: &Mutex<Option<CachedResponse<T>>>,
+    config: &WeatherConfig,
+) -> Option<T> {
+    let key = cache_key(config);
+    let guard = cache.lock().ok()?;
+    guard
+        .as_ref()
+        .filter(|c| c.key == key && c.fetched_at.elapsed() < CACHE_TTL)
+        .map(|c| c.data.clone())
+}
+
+fn store_cached<T>(cache: &Mutex<Option<CachedResponse<T>>>, config: &WeatherConfig, data: T) {
+    if let Ok(mut guard) = cache.lock() {
+        *guard = Some(CachedResponse {
+            key: cache_key(config),
+            fetched_at: Instant::now(),
+            data,
+        });
+    }
+}
+
+/// Location shared by every provider
+fn build_location(config: &WeatherConfig) -> WeatherLocation {
+    WeatherLocation {
+        name: "Toronto".to_string(),
+        latitude: config.latitude,
+        longitude: config.longitude,
+    }
+}
+
+/// The configured timezone, which sets where each day starts and ends
+fn timezone(config: &WeatherConfig) -> Tz {
+    config.timezone.parse().unwrap_or_else(|_| {
+        log::warn!("Weather: Unknown timezone {}, using UTC", config.timezone);
+        Tz::UTC
+    })
+}
+
+async fn fetch_from_provider(
+    client: &Client,
+    config: &WeatherConfig,
+) -> Result<WeatherData, String> {
+    match config.provider {
+        WeatherProviderKind::OpenMeteo => OpenMeteo.fetch_weather(client, config).await,
+        WeatherProviderKind::MetNorway => MetNorway.fetch_weather(client, config).await,
+        WeatherProviderKind::Fixture => FixtureProvider.fetch_weather(client, config).await,
+    }
+}
+
+/// Air quality always comes from Open-Meteo, whichever forecast provider is configured
+pub async fn fetch_air_quality(config: &WeatherConfig) -> Result<AirQuality, String> {
+    if let Some(cached) = get_cached(&AIR_QUALITY_CACHE, config) {
+        return Ok(cached);
+    }
+
+    let client = Client::new();
+    let air_quality = open_meteo::fetch_air_quality(&client, config).await?;
+
+    store_cached(&AIR_QUALITY_CACHE, config, air_quality.clone());
+
+    Ok(air_quality)
+}
+
+pub async fn fetch_weather(config: &WeatherConfig) -> Result<WeatherData, String> {
+    let mut weather = match get_cached(&WEATHER_CACHE, config) {
+        Some(cached) => cached,
+        None => {
+            let client = Client::new();
+            let weather = fetch_from_provider(&client, config).await?;
+            store_cached(&WEATHER_CACHE, config, weather.clone());
+            weather
+        }
+    };
+
+    // Air quality is a nice-to-have, don't fail the whole card without it.
+    // It's cached on its own, so a failed fetch is retried on the next call
+    if weather.air_quality.is_none() && config.provider != WeatherProviderKind::Fixture {
+        weather.air_quality = match fetch_air_quality(config).await {
+            Ok(air_quality) => Some(air_quality),
+            Err(e) => {
+                log::warn!("Weather: {}", e);
+                None
+            }
+        };
+    }
+
+    Ok(weather)
+}
@@ -0,0 +1,301 @@
+use super::{build_location, WeatherProvider};
+use crate::api::{
+    AirQuality, ConditionCategory, DailyWeatherSummary, HourlyWeather, Intensity, PollenCounts,
+    PrecipitationType, WeatherCondition, WeatherData,
+};
+use crate::config::WeatherConfig;
+use chrono::Local;
+use reqwest::Client;
+use serde::Deserialize;
+
+const OPEN_METEO_BASE: &str = "https://api.open-meteo.com/v1/forecast";
+const OPEN_METEO_AIR_QUALITY_BASE: &str = "https://air-quality-api.open-meteo.com/v1/air-quality";
+
+/// Open-Meteo forecast API (default provider)
+pub struct OpenMeteo;
+
+#[derive(Debug, Deserialize)]
+struct OpenMeteoResponse {
+    current: CurrentWeather,
+    hourly: HourlyData,
+    daily: DailyData,
+}
+
+#[derive(Debug, Deserialize)]
+struct CurrentWeather {
+    temperature_2m: f64,
+    apparent_temperature: f64,
+    weather_code: i32,
+    is_day: u8,
+    relative_humidity_2m: i32,
+    wind_speed_10m: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct HourlyData {
+    time: Vec<String>,
+    temperature_2m: Vec<f64>,
+    weather_code: Vec<i32>,
+    is_day: Vec<u8>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DailyData {
+    sunrise: Vec<String>,
+    sunset: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HistoryResponse {
+    hourly: HourlyData,
+    daily: HistoryDailyData,
+}
+
+#[derive(Debug, Deserialize)]
+struct HistoryDailyData {
+    time: Vec<String>,
+    weather_code: Vec<Option<i32>>,
+    temperature_2m_max: Vec<Option<f64>>,
+    temperature_2m_min: Vec<Option<f64>>,
+    precipitation_sum: Vec<Option<f64>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AirQualityResponse {
+    current: CurrentAirQuality,
+}
+
+#[derive(Debug, Deserialize)]
+struct CurrentAirQuality {
+    us_aqi: Option<f64>,
+    pm2_5: Option<f64>,
+    pm10: Option<f64>,
+    ozone: Option<f64>,
+    alder_pollen: Option<f64>,
+    birch_pollen: Option<f64>,
+    grass_pollen: Option<f64>,
+    mugwort_pollen: Option<f64>,
+    olive_pollen: Option<f64>,
+    ragweed_pollen: Option<f64>,
+}
+
+/// Map a US AQI value to its EPA category
+fn map_aqi_category(aqi: Option<i32>) -> &'static str {
+    match aqi {
+        None => "unknown",
+        Some(..=50) => "good",
+        Some(51..=100) => "moderate",
+        Some(101..=150) => "unhealthy-sensitive",
+        Some(151..=200) => "unhealthy",
+        Some(201..=300) => "very-unhealthy",
+        Some(_) => "hazardous",
+    }
+}
+
+/// Map a WMO weather code to a structured condition
+fn map_weather_code(code: i32, is_day: bool) -> WeatherCondition {
+    use ConditionCategory as C;
+    use Intensity::{Heavy, Light, Moderate};
+    use PrecipitationType::{Freezing, Hail, Rain, Snow};
+
+    let (category, intensity, precipitation) = match code {
+        0 | 1 => (C::Clear, None, None),
+        2 => (C::PartlyCloudy, None, None),
+        3 => (C::Cloudy, None, None),
+        45 | 48 => (C::Fog, None, None),
+        51 => (C::Drizzle, Some(Light), Some(Rain)),
+        53 => (C::Drizzle, Some(Moderate), Some(Rain)),
+        55 => (C::Drizzle, Some(Heavy), Some(Rain)),
+        56 => (C::Drizzle, Some(Light), Some(Freezing)),
+        57 => (C::Drizzle, Some(Heavy), Some(Freezing)),
+        61 | 80 => (C::Rain, Some(Light), Some(Rain)),
+        63 | 81 => (C::Rain, Some(Moderate), Some(Rain)),
+        65 | 82 => (C::Rain, Some(Heavy), Some(Rain)),
+        66 => (C::Rain, Some(Light), Some(Freezing)),
+        67 => (C::Rain, Some(Heavy), Some(Freezing)),
+        71 | 77 | 85 => (C::Snow, Some(Light), Some(Snow)),
+        73 => (C::Snow, Some(Moderate), Some(Snow)),
+        75 | 86 => (C::Snow, Some(Heavy), Some(Snow)),
+        95 => (C::Thunderstorm, Some(Moderate), Some(Rain)),
+        96 => (C::Thunderstorm, Some(Moderate), Some(Hail)),
+        99 => (C::Thunderstorm, Some(Heavy), Some(Hail)),
+        _ => (C::Cloudy, None, None),
+    };
+
+    WeatherCondition::new(category, intensity, precipitation, is_day)
+}
+
+pub async fn fetch_air_quality(
+    client: &Client,
+    config: &WeatherConfig,
+) -> Result<AirQuality, String> {
+    let url = format!(
+        "{}?latitude={}&longitude={}&current=us_aqi,pm2_5,pm10,ozone,alder_pollen,birch_pollen,grass_pollen,mugwort_pollen,olive_pollen,ragweed_pollen&timezone={}",
+        OPEN_METEO_AIR_QUALITY_BASE,
+        config.latitude,
+        config.longitude,
+        urlencoding::encode(&config.timezone)
+    );
+
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Air quality API request failed: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Air quality API error: {}", response.status()));
+    }
+
+    let data: AirQualityResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse air quality response: {}", e))?;
+
+    let current = data.current;
+    let aqi = current.us_aqi.map(|v| v.round() as i32);
+
+    Ok(AirQuality {
+        aqi,
+        aqi_category: map_aqi_category(aqi).to_string(),
+        pm25: current.pm2_5,
+        pm10: current.pm10,
+        ozone: current.ozone,
+        pollen: PollenCounts {
+            alder: current.alder_pollen,
+            birch: current.birch_pollen,
+            grass: current.grass_pollen,
+            mugwort: current.mugwort_pollen,
+            olive: current.olive_pollen,
+            ragweed: current.ragweed_pollen,
+        },
+        last_updated: Local::now().to_rfc3339(),
+    })
+}
+
+fn build_hourly(hourly: &HourlyData, offset: usize) -> Vec<HourlyWeather> {
+    (0..24)
+        .map(|hour| {
+            let index = offset + hour;
+            let temp = hourly.temperature_2m.get(index).copied().unwrap_or(0.0);
+            let code = hourly.weather_code.get(index).copied().unwrap_or(0);
+            let is_day = hourly.is_day.get(index).copied().unwrap_or(1) == 1;
+
+            HourlyWeather {
+                hour: hour as i32,
+                temperature: temp.round() as i32,
+                condition: map_weather_code(code, is_day),
+            }
+        })
+        .collect()
+}
+
+/// Daily summaries for the last `past_days` days plus today, using Open-Meteo's `past_days`
+pub async fn fetch_daily_history(
+    client: &Client,
+    config: &WeatherConfig,
+    past_days: u32,
+) -> Result<Vec<DailyWeatherSummary>, String> {
+    let url = format!(
+        "{}?latitude={}&longitude={}&hourly=temperature_2m,weather_code,is_day&daily=weather_code,temperature_2m_max,temperature_2m_min,precipitation_sum&timezone={}&past_days={}&forecast_days=1",
+        OPEN_METEO_BASE,
+        config.latitude,
+        config.longitude,
+        urlencoding::encode(&config.timezone),
+        past_days
+    );
+
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Weather history request failed: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Weather history API error: {}", response.status()));
+    }
+
+    let data: HistoryResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse weather history response: {}", e))?;
+
+    let daily = &data.daily;
+    let summaries = daily
+        .time
+        .iter()
+        .enumerate()
+        .filter_map(|(i, date)| {
+            // Skip days the model has no data for yet
+            let high = daily.temperature_2m_max.get(i).copied().flatten()?;
+            let low = daily.temperature_2m_min.get(i).copied().flatten()?;
+            let code = daily.weather_code.get(i).copied().flatten().unwrap_or(0);
+
+            Some(DailyWeatherSummary {
+                date: date.clone(),
+                high: (high * 10.0).round() / 10.0,
+                low: (low * 10.0).round() / 10.0,
+                precipitation: daily
+                    .precipitation_sum
+                    .get(i)
+                    .copied()
+                    .flatten()
+                    .unwrap_or(0.0),
+                condition: map_weather_code(code, true),
+                hourly: build_hourly(&data.hourly, i * 24),
+            })
+        })
+        .collect();
+
+    Ok(summaries)
+}
+
+impl WeatherProvider for OpenMeteo {
+    async fn fetch_weather(
+        &self,
+        client: &Client,
+        config: &WeatherConfig,
+    ) -> Result<WeatherData, String> {
+        let url = format!(
+            "{}?latitude={}&longitude={}&current=temperature_2m,apparent_temperature,weather_code,is_day,relative_humidity_2m,wind_speed_10m&hourly=temperature_2m,weather_code,is_day&daily=sunrise,sunset&timezone={}&forecast_days=1",
+            OPEN_METEO_BASE,
+            config.latitude,
+            config.longitude,
+            urlencoding::encode(&config.timezone)
+        );
+
+        let response = client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| format!("Weather API request failed: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("Weather API error: {}", response.status()));
+        }
+
+        let data: OpenMeteoResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse weather response: {}", e))?;
+
+        // Build hourly forecast for fixed 0-23 hours (12am to 11pm)
+        let hourly_forecast = build_hourly(&data.hourly, 0);
+
+        Ok(WeatherData {
+            location: build_location(config),
+            condition: map_weather_code(data.current.weather_code, data.current.is_day == 1),
+            temperature: data.current.temperature_2m.round() as i32,
+            feels_like: data.current.apparent_temperature.round() as i32,
+            humidity: data.current.relative_humidity_2m,
+            wind_speed: data.current.wind_speed_10m.round() as i32,
+            unit: "celsius".to_string(),
+            sunrise: data.daily.sunrise.first().cloned().unwrap_or_default(),
+            sunset: data.daily.sunset.first().cloned().unwrap_or_default(),
+            hourly_forecast,
+            air_quality: None,
+            measured: Vec::new(),
+            last_updated: Local::now().to_rfc3339(),
+        })
+    }
+}
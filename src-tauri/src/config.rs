@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
 use thiserror::Error;
@@ -20,9 +21,13 @@ pub struct AppConfig {
     pub weather: WeatherConfig,
     pub stocks: StocksConfig,
     pub ticktick: TickTickConfig,
+    #[serde(default)]
+    pub tasks: TasksConfig,
     pub google_calendar: GoogleCalendarConfig,
     pub timezones: TimezonesConfig,
     pub display: DisplayConfig,
+    #[serde(default)]
+    pub sensors: SensorsConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,24 +35,398 @@ pub struct WeatherConfig {
     pub latitude: f64,
     pub longitude: f64,
     pub timezone: String,
+    #[serde(default)]
+    pub provider: WeatherProviderKind,
+    /// JSON file used by the fixture provider (default: weather.fixture.json in the config dir)
+    #[serde(default)]
+    pub fixture_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum WeatherProviderKind {
+    /// Open-Meteo forecast API
+    #[default]
+    OpenMeteo,
+    /// MET Norway Locationforecast API
+    MetNorway,
+    /// Local JSON fixture, for offline development
+    Fixture,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SensorsConfig {
+    #[serde(default)]
+    pub sources: Vec<SensorSource>,
+}
+
+/// A local sensor publishing temperature, humidity and/or CO2
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SensorSource {
+    pub name: String,
+    #[serde(default)]
+    pub location: SensorLocation,
+    #[serde(flatten)]
+    pub kind: SensorSourceKind,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SensorLocation {
+    #[default]
+    Indoor,
+    Outdoor,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SensorSourceKind {
+    /// JSON payload on an MQTT topic (retained messages are read immediately)
+    Mqtt {
+        host: String,
+        #[serde(default = "default_mqtt_port")]
+        port: u16,
+        topic: String,
+        #[serde(default)]
+        username: Option<String>,
+        #[serde(default)]
+        password: Option<String>,
+    },
+    /// HTTP endpoint returning a JSON object
+    Http { url: String },
+    /// Plain value files, e.g. /sys/bus/iio/devices/iio:device0/in_temp_input
+    File {
+        #[serde(default)]
+        temperature_path: Option<String>,
+        #[serde(default)]
+        humidity_path: Option<String>,
+        #[serde(default)]
+        co2_path: Option<String>,
+        /// Multipliers applied to each raw value (IIO reports milli-units, so use 0.001)
+        #[serde(default = "default_sensor_scale")]
+        temperature_scale: f64,
+        #[serde(default = "default_sensor_scale")]
+        humidity_scale: f64,
+        #[serde(default = "default_sensor_scale")]
+        co2_scale: f64,
+    },
+}
+
+fn default_mqtt_port() -> u16 {
+    1883
+}
+
+fn default_sensor_scale() -> f64 {
+    1.0
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StocksConfig {
-    pub tickers: Vec<String>,
+    /// Plain symbols ("TRI") or tables with a type, display name and decimals
+    pub tickers: Vec<WatchlistEntry>,
+    /// Where quotes come from unless overridden per ticker
+    #[serde(default)]
+    pub provider: MarketDataProviderKind,
+    /// Per-ticker provider overrides, e.g. `"TRI" = "stooq"`
+    #[serde(default)]
+    pub ticker_providers: BTreeMap<String, MarketDataProviderKind>,
+    /// JSON file used by the fixture provider (default: stocks.fixture.json in the config dir)
+    #[serde(default)]
+    pub fixture_path: Option<String>,
+    /// ISO currency code (e.g. "CAD") to also report prices and portfolio totals in
+    #[serde(default)]
+    pub base_currency: Option<String>,
+    /// Positions held, used by the portfolio view
+    #[serde(default)]
+    pub holdings: Vec<Holding>,
+    /// How often to refresh while any market is open (including pre/post-market)
+    #[serde(default = "default_stocks_refresh_interval")]
+    pub refresh_interval_minutes: u32,
+    /// How often to refresh while every market is closed
+    #[serde(default = "default_stocks_closed_refresh_interval")]
+    pub closed_refresh_interval_minutes: u32,
+    /// Threshold notifications, checked after each refresh
+    #[serde(default)]
+    pub alerts: Vec<AlertRule>,
+}
+
+/// A watchlist entry, either a bare Yahoo-style symbol or a detailed table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum WatchlistEntry {
+    Symbol(String),
+    Detailed(WatchlistItem),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchlistItem {
+    pub symbol: String,
+    /// Inferred from the symbol when not set
+    #[serde(default)]
+    pub kind: Option<AssetKind>,
+    /// Shown instead of the provider's name
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Price decimals, overriding the asset type's default
+    #[serde(default)]
+    pub decimals: Option<i32>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetKind {
+    Equity,
+    /// Trades around the clock, e.g. "BTC-USD"
+    Crypto,
+    /// Currency pair, e.g. "USDCAD=X"
+    Fx,
+    /// Index level in points, e.g. "^GSPC"
+    Index,
+    /// Futures contract, e.g. "GC=F"
+    Commodity,
+}
+
+impl WatchlistEntry {
+    pub fn symbol(&self) -> &str {
+        match self {
+            WatchlistEntry::Symbol(symbol) => symbol,
+            WatchlistEntry::Detailed(item) => &item.symbol,
+        }
+    }
+
+    pub fn kind(&self) -> AssetKind {
+        match self {
+            WatchlistEntry::Detailed(WatchlistItem {
+                kind: Some(kind), ..
+            }) => *kind,
+            _ => AssetKind::infer(self.symbol()),
+        }
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            WatchlistEntry::Symbol(_) => None,
+            WatchlistEntry::Detailed(item) => item.name.as_deref(),
+        }
+    }
+
+    pub fn decimals(&self) -> Option<i32> {
+        match self {
+            WatchlistEntry::Symbol(_) => None,
+            WatchlistEntry::Detailed(item) => item.decimals,
+        }
+    }
+}
+
+impl AssetKind {
+    /// Guess the asset type from Yahoo's symbol conventions
+    pub fn infer(symbol: &str) -> Self {
+        if symbol.starts_with('^') {
+            return AssetKind::Index;
+        }
+        if symbol.ends_with("=X") {
+            return AssetKind::Fx;
+        }
+        if symbol.ends_with("=F") {
+            return AssetKind::Commodity;
+        }
+
+        // Crypto pairs are "<coin>-<fiat>", e.g. "BTC-USD" or "ETH-CAD"
+        match symbol.rsplit_once('-') {
+            Some((coin, fiat))
+                if !coin.contains('.')
+                    && fiat.len() == 3
+                    && fiat.chars().all(|c| c.is_ascii_uppercase()) =>
+            {
+                AssetKind::Crypto
+            }
+            _ => AssetKind::Equity,
+        }
+    }
+}
+
+impl StocksConfig {
+    /// Symbols of every watchlist entry, in order
+    pub fn symbols(&self) -> Vec<String> {
+        self.tickers
+            .iter()
+            .map(|t| t.symbol().to_string())
+            .collect()
+    }
+
+    /// Watchlist entry for a symbol, if it's on the watchlist
+    pub fn entry(&self, symbol: &str) -> Option<&WatchlistEntry> {
+        self.tickers
+            .iter()
+            .find(|t| t.symbol().eq_ignore_ascii_case(symbol))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertRule {
+    pub ticker: String,
+    #[serde(flatten)]
+    pub condition: AlertCondition,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AlertCondition {
+    /// Price at or above a level
+    Above { price: f64 },
+    /// Price at or below a level
+    Below { price: f64 },
+    /// Day change of at least this many percent, in either direction
+    PercentChange { percent: f64 },
+    /// Price reaches the 52-week high
+    FiftyTwoWeekHigh,
+    /// Price reaches the 52-week low
+    FiftyTwoWeekLow,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MarketDataProviderKind {
+    /// Yahoo Finance chart API (unofficial)
+    #[default]
+    Yahoo,
+    /// Stooq CSV downloads, end-of-day only
+    Stooq,
+    /// Local JSON fixture, for offline development
+    Fixture,
+}
+
+fn default_stocks_refresh_interval() -> u32 {
+    5
+}
+
+fn default_stocks_closed_refresh_interval() -> u32 {
+    60
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Holding {
+    pub ticker: String,
+    pub lots: Vec<Lot>,
+}
+
+/// A single purchase of shares
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Lot {
+    pub shares: f64,
+    /// Price paid per share, in the ticker's currency
+    pub cost_basis: f64,
+    /// Purchase date in "YYYY-MM-DD" format
+    #[serde(default)]
+    pub date: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TickTickConfig {
+    /// OAuth app credentials for `start_ticktick_oauth`. Not needed when an
+    /// access token is pasted in by hand
+    #[serde(default)]
+    pub client_id: String,
+    #[serde(default)]
+    pub client_secret: String,
+    #[serde(default)]
     pub access_token: String,
+    #[serde(default)]
+    pub refresh_token: String,
+    #[serde(default)]
+    pub token_expiry: String,
     #[serde(default = "default_ticktick_refresh_interval")]
     pub refresh_interval_minutes: u32,
+    /// Refreshes within this many seconds of the last sync are served from
+    /// the local task store instead of the API
+    #[serde(default = "default_ticktick_min_sync_interval")]
+    pub min_sync_interval_seconds: u64,
+    /// Named task queries, usable as `query_tasks` lists
+    #[serde(default)]
+    pub smart_lists: Vec<SmartList>,
+}
+
+/// A saved task query, e.g. `{ name = "Urgent", query = "overdue priority>=3" }`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmartList {
+    pub name: String,
+    pub query: String,
+    #[serde(default)]
+    pub group_by: TaskGrouping,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskGrouping {
+    #[default]
+    None,
+    Project,
+    /// Overdue, today, tomorrow, this week, later, no date
+    Due,
+    Priority,
+}
+
+/// Where tasks come from. TickTick keeps its own `[ticktick]` section
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct TasksConfig {
+    #[serde(default)]
+    pub provider: TaskProviderKind,
+    #[serde(default)]
+    pub caldav: CalDavConfig,
+    #[serde(default)]
+    pub todoist: TodoistConfig,
+    /// Task file used by the file provider (default: tasks.md in the config dir).
+    /// Files ending in .txt are read as todo.txt, anything else as a Markdown checklist
+    #[serde(default)]
+    pub file_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq)]
+pub enum TaskProviderKind {
+    /// TickTick open API
+    #[default]
+    #[serde(rename = "ticktick")]
+    TickTick,
+    /// VTODOs in a CalDAV task collection, e.g. Radicale or Nextcloud
+    #[serde(rename = "caldav")]
+    CalDav,
+    /// Todoist REST API
+    #[serde(rename = "todoist")]
+    Todoist,
+    /// A local todo.txt or Markdown checklist
+    #[serde(rename = "file")]
+    File,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CalDavConfig {
+    /// URL of the task collection, e.g. http://localhost:5232/user/tasks/
+    #[serde(default)]
+    pub url: String,
+    #[serde(default)]
+    pub username: String,
+    #[serde(default)]
+    pub password: String,
+    /// Shown as the list name (default: "Tasks")
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct TodoistConfig {
+    /// From Todoist settings, under Integrations > Developer
+    #[serde(default)]
+    pub api_token: String,
 }
 
 fn default_ticktick_refresh_interval() -> u32 {
     15
 }
 
+fn default_ticktick_min_sync_interval() -> u64 {
+    60
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CalendarSource {
     pub id: String,
@@ -115,19 +494,36 @@ impl Default for AppConfig {
                 latitude: 43.6532,
                 longitude: -79.3832,
                 timezone: "America/Toronto".to_string(),
+                provider: WeatherProviderKind::OpenMeteo,
+                fixture_path: None,
             },
             stocks: StocksConfig {
                 tickers: vec![
-                    "TRI".to_string(),
-                    "VEQT.TO".to_string(),
-                    "VGRO.TO".to_string(),
-                    "ZGLD.TO".to_string(),
+                    WatchlistEntry::Symbol("TRI".to_string()),
+                    WatchlistEntry::Symbol("VEQT.TO".to_string()),
+                    WatchlistEntry::Symbol("VGRO.TO".to_string()),
+                    WatchlistEntry::Symbol("ZGLD.TO".to_string()),
                 ],
+                provider: MarketDataProviderKind::Yahoo,
+                ticker_providers: BTreeMap::new(),
+                fixture_path: None,
+                base_currency: None,
+                holdings: vec![],
+                refresh_interval_minutes: 5,
+                closed_refresh_interval_minutes: 60,
+                alerts: vec![],
             },
             ticktick: TickTickConfig {
+                client_id: String::new(),
+                client_secret: String::new(),
                 access_token: String::new(),
+                refresh_token: String::new(),
+                token_expiry: String::new(),
                 refresh_interval_minutes: 15,
+                min_sync_interval_seconds: 60,
+                smart_lists: vec![],
             },
+            tasks: TasksConfig::default(),
             google_calendar: GoogleCalendarConfig {
                 client_id: String::new(),
                 client_secret: String::new(),
@@ -165,6 +561,7 @@ impl Default for AppConfig {
                 fullscreen: false,
                 theme_mode: ThemeMode::AutoSun,
             },
+            sensors: SensorsConfig::default(),
         }
     }
 }
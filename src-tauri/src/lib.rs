@@ -1,11 +1,18 @@
 mod api;
 mod config;
 mod oauth;
+mod sensors;
 mod timeline;
 
-use api::{CalendarEvent, CalendarListEntry, StockData, TickTickData, WeatherData};
-use config::{AppConfig, CalendarSource};
-use tauri::{Manager, WebviewWindow};
+use api::{
+    Agenda, AirQuality, CalendarEvent, CalendarListEntry, ChartRange, CompletedRange,
+    CompletedTasks, FetchStatus, NewTickTickTask, PendingTaskAction, Portfolio, StockChart,
+    StocksData, SymbolMatch, TaskQueryResult, TasksData, TickTickData, TickTickTask,
+    TickTickTaskUpdate, TickerValidation, WeatherData, WeatherHistory,
+};
+use config::{AppConfig, CalendarSource, TaskGrouping};
+use sensors::SensorReading;
+use tauri::{AppHandle, Emitter, Manager, WebviewWindow};
 
 // Tauri Commands
 
@@ -15,26 +22,183 @@ fn get_config() -> Result<AppConfig, String> {
 }
 
 #[tauri::command]
-fn save_config(new_config: AppConfig) -> Result<(), String> {
+async fn save_config(new_config: AppConfig) -> Result<(), String> {
+    // Catch typos in newly added tickers before they're written. Timeouts and
+    // network errors don't block saving, only symbols the provider rejects
+    let current = config::load_config().ok();
+    let added: Vec<String> = new_config
+        .stocks
+        .symbols()
+        .into_iter()
+        .filter(|t| current.as_ref().and_then(|c| c.stocks.entry(t)).is_none())
+        .collect();
+
+    if !added.is_empty() {
+        let unknown: Vec<String> = api::stocks::validate_tickers(&added, &new_config.stocks)
+            .await?
+            .into_iter()
+            .filter(|v| v.status == FetchStatus::NotFound)
+            .map(|v| v.ticker)
+            .collect();
+
+        if !unknown.is_empty() {
+            return Err(format!("Unknown tickers: {}", unknown.join(", ")));
+        }
+    }
+
     config::save_config(&new_config).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 async fn fetch_weather() -> Result<WeatherData, String> {
     let config = config::load_config().map_err(|e| e.to_string())?;
-    api::weather::fetch_weather(&config.weather).await
+    let mut weather = api::weather::fetch_weather(&config.weather).await?;
+    weather.measured = sensors::fetch_local_sensors(&config.sensors).await;
+    Ok(weather)
+}
+
+#[tauri::command]
+async fn get_weather_history(days: u32) -> Result<WeatherHistory, String> {
+    let config = config::load_config().map_err(|e| e.to_string())?;
+    api::weather::get_weather_history(&config.weather, days).await
+}
+
+#[tauri::command]
+async fn fetch_local_sensors() -> Result<Vec<SensorReading>, String> {
+    let config = config::load_config().map_err(|e| e.to_string())?;
+    Ok(sensors::fetch_local_sensors(&config.sensors).await)
+}
+
+#[tauri::command]
+async fn fetch_air_quality() -> Result<AirQuality, String> {
+    let config = config::load_config().map_err(|e| e.to_string())?;
+    api::weather::fetch_air_quality(&config.weather).await
+}
+
+#[tauri::command]
+async fn fetch_stocks(app: AppHandle) -> Result<StocksData, String> {
+    let config = config::load_config().map_err(|e| e.to_string())?;
+    let data = api::stocks::fetch_stocks(&config.stocks.symbols(), &config.stocks).await?;
+
+    for alert in api::alerts::evaluate_alerts(&data.stocks, &config.stocks.alerts) {
+        if let Err(e) = app.emit("stock-alert", alert) {
+            log::warn!("Stocks: Failed to emit alert: {}", e);
+        }
+    }
+
+    Ok(data)
+}
+
+#[tauri::command]
+async fn fetch_stock_chart(ticker: String, range: ChartRange) -> Result<StockChart, String> {
+    let config = config::load_config().map_err(|e| e.to_string())?;
+    api::stocks::fetch_stock_chart(&config.stocks, &ticker, range).await
+}
+
+#[tauri::command]
+async fn search_symbols(query: String) -> Result<Vec<SymbolMatch>, String> {
+    api::stocks::search_symbols(&query).await
+}
+
+#[tauri::command]
+async fn validate_tickers(tickers: Vec<String>) -> Result<Vec<TickerValidation>, String> {
+    let config = config::load_config().map_err(|e| e.to_string())?;
+    api::stocks::validate_tickers(&tickers, &config.stocks).await
 }
 
 #[tauri::command]
-async fn fetch_stocks() -> Result<Vec<StockData>, String> {
+async fn fetch_portfolio() -> Result<Portfolio, String> {
     let config = config::load_config().map_err(|e| e.to_string())?;
-    api::stocks::fetch_stocks(&config.stocks.tickers).await
+    api::portfolio::fetch_portfolio(&config.stocks).await
 }
 
 #[tauri::command]
-async fn fetch_ticktick_tasks() -> Result<TickTickData, String> {
+async fn fetch_ticktick_tasks(force: Option<bool>) -> Result<TickTickData, String> {
     let config = config::load_config().map_err(|e| e.to_string())?;
-    api::ticktick::fetch_ticktick(&config.ticktick.access_token).await
+    let access_token = oauth::ticktick::get_valid_access_token().await?;
+    api::ticktick::fetch_ticktick(&access_token, &config.ticktick, force.unwrap_or(false)).await
+}
+
+/// Filter and group open tasks, either with a query string or a smart list
+/// from the config. An explicit `group_by` overrides the smart list's
+#[tauri::command]
+async fn query_tasks(
+    query: Option<String>,
+    list: Option<String>,
+    group_by: Option<TaskGrouping>,
+) -> Result<TaskQueryResult, String> {
+    let config = config::load_config().map_err(|e| e.to_string())?;
+
+    let (query, default_grouping) = match list {
+        Some(name) => {
+            let smart_list = config
+                .ticktick
+                .smart_lists
+                .iter()
+                .find(|l| l.name.eq_ignore_ascii_case(&name))
+                .ok_or_else(|| format!("No smart list named '{}'", name))?;
+            (smart_list.query.clone(), smart_list.group_by)
+        }
+        None => (query.unwrap_or_default(), TaskGrouping::None),
+    };
+
+    let access_token = oauth::ticktick::get_valid_access_token().await?;
+    let data = api::ticktick::fetch_ticktick(&access_token, &config.ticktick, false).await?;
+    api::ticktick::query_tasks(&data, &query, group_by.unwrap_or(default_grouping))
+}
+
+#[tauri::command]
+fn get_completed_tasks(range: CompletedRange) -> CompletedTasks {
+    api::ticktick::get_completed_tasks(range)
+}
+
+/// Open tasks from the provider set in `[tasks]`
+#[tauri::command]
+async fn fetch_tasks() -> Result<TasksData, String> {
+    let config = config::load_config().map_err(|e| e.to_string())?;
+    api::tasks::fetch_tasks(&config).await
+}
+
+/// Complete a task from `fetch_tasks` with the provider set in `[tasks]`
+#[tauri::command]
+async fn complete_provider_task(list_id: String, task_id: String) -> Result<(), String> {
+    let config = config::load_config().map_err(|e| e.to_string())?;
+    api::tasks::complete_task(&config, &list_id, &task_id).await
+}
+
+#[tauri::command]
+async fn complete_task(project_id: String, task_id: String) -> Result<(), String> {
+    let access_token = oauth::ticktick::get_valid_access_token().await?;
+    api::ticktick::complete_task(&access_token, &project_id, &task_id).await
+}
+
+#[tauri::command]
+async fn create_task(task: NewTickTickTask) -> Result<TickTickTask, String> {
+    let access_token = oauth::ticktick::get_valid_access_token().await?;
+    api::ticktick::create_task(&access_token, task).await
+}
+
+#[tauri::command]
+async fn update_task(update: TickTickTaskUpdate) -> Result<TickTickTask, String> {
+    let access_token = oauth::ticktick::get_valid_access_token().await?;
+    api::ticktick::update_task(&access_token, update).await
+}
+
+/// Task changes made offline that haven't reached TickTick, or that it rejected
+#[tauri::command]
+fn get_pending_task_actions() -> Vec<PendingTaskAction> {
+    api::ticktick::get_pending_actions()
+}
+
+#[tauri::command]
+async fn replay_task_actions() -> Result<Vec<PendingTaskAction>, String> {
+    let access_token = oauth::ticktick::get_valid_access_token().await?;
+    api::ticktick::replay_pending_actions(&access_token).await
+}
+
+#[tauri::command]
+async fn discard_task_action(id: String) -> Result<(), String> {
+    api::ticktick::discard_pending_action(&id).await
 }
 
 #[tauri::command]
@@ -42,6 +206,12 @@ async fn fetch_calendar_events() -> Result<Vec<CalendarEvent>, String> {
     api::calendar::fetch_calendar_events().await
 }
 
+#[tauri::command]
+async fn get_agenda(days: Option<u32>) -> Result<Agenda, String> {
+    let config = config::load_config().map_err(|e| e.to_string())?;
+    api::agenda::get_agenda(&config, days.unwrap_or(7)).await
+}
+
 #[tauri::command]
 async fn fetch_calendar_list() -> Result<Vec<CalendarListEntry>, String> {
     api::calendar::fetch_calendar_list().await
@@ -66,7 +236,7 @@ async fn start_google_oauth() -> Result<String, String> {
 #[tauri::command]
 async fn complete_google_oauth() -> Result<(), String> {
     // Wait for the OAuth callback
-    let code = tokio::task::spawn_blocking(|| oauth::google::wait_for_oauth_callback())
+    let code = tokio::task::spawn_blocking(oauth::wait_for_oauth_callback)
         .await
         .map_err(|e| format!("Task failed: {}", e))??;
 
@@ -74,6 +244,23 @@ async fn complete_google_oauth() -> Result<(), String> {
     oauth::google::exchange_code_for_tokens(&code).await
 }
 
+#[tauri::command]
+async fn start_ticktick_oauth() -> Result<String, String> {
+    let config = config::load_config().map_err(|e| e.to_string())?;
+    oauth::ticktick::start_oauth_flow(&config.ticktick)
+}
+
+#[tauri::command]
+async fn complete_ticktick_oauth() -> Result<(), String> {
+    // Wait for the OAuth callback
+    let code = tokio::task::spawn_blocking(oauth::wait_for_oauth_callback)
+        .await
+        .map_err(|e| format!("Task failed: {}", e))??;
+
+    // Exchange the code for tokens
+    oauth::ticktick::exchange_code_for_tokens(&code).await
+}
+
 #[tauri::command]
 fn toggle_fullscreen(window: WebviewWindow) -> Result<(), String> {
     let is_fullscreen = window.is_fullscreen().map_err(|e| e.to_string())?;
@@ -153,14 +340,34 @@ pub fn run() {
             get_config,
             save_config,
             fetch_weather,
+            fetch_air_quality,
+            get_weather_history,
+            fetch_local_sensors,
             fetch_stocks,
+            fetch_stock_chart,
+            search_symbols,
+            validate_tickers,
+            fetch_portfolio,
             fetch_ticktick_tasks,
+            query_tasks,
+            get_completed_tasks,
+            fetch_tasks,
+            complete_provider_task,
+            complete_task,
+            create_task,
+            update_task,
+            get_pending_task_actions,
+            replay_task_actions,
+            discard_task_action,
             fetch_calendar_events,
             fetch_calendar_list,
+            get_agenda,
             get_calendar_sources,
             is_calendar_configured,
             start_google_oauth,
             complete_google_oauth,
+            start_ticktick_oauth,
+            complete_ticktick_oauth,
             toggle_fullscreen,
             get_fullscreen_state,
             get_refresh_intervals,
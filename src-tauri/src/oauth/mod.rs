@@ -1 +1,226 @@
 pub mod google;
+pub mod ticktick;
+
+use chrono::{DateTime, Duration, Utc};
+use reqwest::Client;
+use serde::Deserialize;
+use std::sync::mpsc;
+use std::thread;
+use tiny_http::{Response, Server};
+use url::Url;
+
+/// Both providers redirect to the same local callback server
+pub const REDIRECT_URI: &str = "http://localhost:8847/oauth/callback";
+const CALLBACK_ADDRESS: &str = "127.0.0.1:8847";
+
+/// Endpoints and scopes for an OAuth 2.0 authorization code flow
+pub struct OAuthProvider {
+    pub name: &'static str,
+    pub auth_url: &'static str,
+    pub token_url: &'static str,
+    pub scopes: &'static str,
+    /// Extra query parameters for the authorize URL
+    pub auth_params: &'static [(&'static str, &'static str)],
+    /// Send the client credentials to the token endpoint as HTTP Basic auth
+    /// instead of form fields
+    pub basic_auth: bool,
+    /// Repeat the scopes when exchanging the code for tokens
+    pub scope_on_exchange: bool,
+}
+
+/// The app's registration with a provider
+pub struct OAuthClient<'a> {
+    pub client_id: &'a str,
+    pub client_secret: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_in: Option<i64>,
+}
+
+/// Tokens from a code exchange or refresh
+pub struct OAuthTokens {
+    pub access_token: String,
+    /// Only present when the provider issues (or rotates) a refresh token
+    pub refresh_token: Option<String>,
+    /// RFC 3339, or empty when the provider doesn't say
+    pub expiry: String,
+}
+
+impl From<TokenResponse> for OAuthTokens {
+    fn from(tokens: TokenResponse) -> Self {
+        OAuthTokens {
+            access_token: tokens.access_token,
+            refresh_token: tokens.refresh_token,
+            expiry: tokens
+                .expires_in
+                .map(|seconds| (Utc::now() + Duration::seconds(seconds)).to_rfc3339())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Open the provider's consent page in the browser, returning its URL
+pub fn start_oauth_flow(provider: &OAuthProvider, client: &OAuthClient) -> Result<String, String> {
+    if client.client_id.is_empty() || client.client_secret.is_empty() {
+        return Err(format!(
+            "{} client_id and client_secret must be configured",
+            provider.name
+        ));
+    }
+
+    let mut auth_url = format!(
+        "{}?client_id={}&redirect_uri={}&response_type=code&scope={}",
+        provider.auth_url,
+        urlencoding::encode(client.client_id),
+        urlencoding::encode(REDIRECT_URI),
+        urlencoding::encode(provider.scopes)
+    );
+    for (key, value) in provider.auth_params {
+        auth_url.push_str(&format!("&{}={}", key, urlencoding::encode(value)));
+    }
+
+    // Open browser
+    if let Err(e) = open::that(&auth_url) {
+        return Err(format!("Failed to open browser: {}", e));
+    }
+
+    Ok(auth_url)
+}
+
+pub fn wait_for_oauth_callback() -> Result<String, String> {
+    let (tx, rx) = mpsc::channel();
+
+    // Start a temporary HTTP server to receive the callback
+    thread::spawn(move || {
+        let server = match Server::http(CALLBACK_ADDRESS) {
+            Ok(s) => s,
+            Err(e) => {
+                let _ = tx.send(Err(format!("Failed to start callback server: {}", e)));
+                return;
+            }
+        };
+
+        // Wait for a single request with timeout
+        if let Some(request) = server.incoming_requests().next() {
+            let url = request.url().to_string();
+
+            // Parse the authorization code from the callback URL
+            if let Ok(parsed) = Url::parse(&format!("http://localhost{}", url)) {
+                if let Some(code) = parsed.query_pairs().find(|(k, _)| k == "code") {
+                    // Send success response to browser
+                    let response = Response::from_string(
+                        "<html><body><h1>Authorization successful!</h1><p>You can close this window and return to inkdash.</p></body></html>"
+                    ).with_header(
+                        tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"text/html"[..]).unwrap()
+                    );
+                    let _ = request.respond(response);
+                    let _ = tx.send(Ok(code.1.to_string()));
+                    return;
+                }
+
+                // Check for error
+                if let Some(error) = parsed.query_pairs().find(|(k, _)| k == "error") {
+                    let response = Response::from_string(
+                        format!("<html><body><h1>Authorization failed</h1><p>{}</p></body></html>", error.1)
+                    );
+                    let _ = request.respond(response);
+                    let _ = tx.send(Err(format!("OAuth error: {}", error.1)));
+                    return;
+                }
+            }
+
+            let response = Response::from_string("Invalid callback");
+            let _ = request.respond(response);
+            let _ = tx.send(Err("Invalid OAuth callback".to_string()));
+        }
+    });
+
+    // Wait for the callback with a timeout
+    rx.recv_timeout(std::time::Duration::from_secs(300))
+        .map_err(|_| "OAuth callback timed out".to_string())?
+}
+
+async fn request_tokens(
+    provider: &OAuthProvider,
+    client: &OAuthClient<'_>,
+    params: &[(&str, &str)],
+    action: &str,
+) -> Result<OAuthTokens, String> {
+    let mut request = Client::new().post(provider.token_url);
+    let mut form = Vec::new();
+    if provider.basic_auth {
+        request = request.basic_auth(client.client_id, Some(client.client_secret));
+    } else {
+        form.push(("client_id", client.client_id));
+        form.push(("client_secret", client.client_secret));
+    }
+    form.extend_from_slice(params);
+
+    let response = request
+        .form(&form)
+        .send()
+        .await
+        .map_err(|e| format!("{} failed: {}", action, e))?;
+
+    if !response.status().is_success() {
+        let error_text = response.text().await.unwrap_or_default();
+        return Err(format!("{} error: {}", action, error_text));
+    }
+
+    let tokens: TokenResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse token response: {}", e))?;
+
+    Ok(tokens.into())
+}
+
+pub async fn exchange_code_for_tokens(
+    provider: &OAuthProvider,
+    client: &OAuthClient<'_>,
+    code: &str,
+) -> Result<OAuthTokens, String> {
+    let mut params = vec![
+        ("code", code),
+        ("redirect_uri", REDIRECT_URI),
+        ("grant_type", "authorization_code"),
+    ];
+    if provider.scope_on_exchange {
+        params.push(("scope", provider.scopes));
+    }
+
+    request_tokens(provider, client, &params, "Token exchange").await
+}
+
+pub async fn refresh_access_token(
+    provider: &OAuthProvider,
+    client: &OAuthClient<'_>,
+    refresh_token: &str,
+) -> Result<OAuthTokens, String> {
+    if refresh_token.is_empty() {
+        return Err("No refresh token available. Please re-authenticate.".to_string());
+    }
+
+    request_tokens(
+        provider,
+        client,
+        &[
+            ("refresh_token", refresh_token),
+            ("grant_type", "refresh_token"),
+        ],
+        "Token refresh",
+    )
+    .await
+}
+
+/// Whether a stored RFC 3339 expiry is within five minutes. Tokens without
+/// a known expiry are used until the API rejects them
+pub fn expires_soon(token_expiry: &str) -> bool {
+    DateTime::parse_from_rfc3339(token_expiry)
+        .map(|expiry| expiry.with_timezone(&Utc) < Utc::now() + Duration::minutes(5))
+        .unwrap_or(false)
+}
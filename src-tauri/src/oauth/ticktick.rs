@@ -0,0 +1,77 @@
+use super::{OAuthClient, OAuthProvider, OAuthTokens};
+use crate::config::{load_config, save_config, TickTickConfig};
+
+const TICKTICK: OAuthProvider = OAuthProvider {
+    name: "TickTick",
+    auth_url: "https://ticktick.com/oauth/authorize",
+    token_url: "https://ticktick.com/oauth/token",
+    scopes: "tasks:read tasks:write",
+    auth_params: &[],
+    // TickTick's token endpoint only accepts the client credentials as Basic
+    // auth, and expects the scopes again on the code exchange
+    basic_auth: true,
+    scope_on_exchange: true,
+};
+
+fn oauth_client(config: &TickTickConfig) -> OAuthClient<'_> {
+    OAuthClient {
+        client_id: &config.client_id,
+        client_secret: &config.client_secret,
+    }
+}
+
+fn store_tokens(tokens: OAuthTokens) -> Result<String, String> {
+    let mut config = load_config().map_err(|e| format!("Failed to load config: {}", e))?;
+
+    config.ticktick.access_token = tokens.access_token.clone();
+    if let Some(refresh_token) = tokens.refresh_token {
+        config.ticktick.refresh_token = refresh_token;
+    }
+    config.ticktick.token_expiry = tokens.expiry;
+
+    save_config(&config).map_err(|e| format!("Failed to save config: {}", e))?;
+
+    Ok(tokens.access_token)
+}
+
+pub fn start_oauth_flow(config: &TickTickConfig) -> Result<String, String> {
+    super::start_oauth_flow(&TICKTICK, &oauth_client(config))
+}
+
+pub async fn exchange_code_for_tokens(code: &str) -> Result<(), String> {
+    let config = load_config().map_err(|e| format!("Failed to load config: {}", e))?;
+    let tokens =
+        super::exchange_code_for_tokens(&TICKTICK, &oauth_client(&config.ticktick), code).await?;
+
+    store_tokens(tokens).map(|_| ())
+}
+
+pub async fn refresh_access_token() -> Result<String, String> {
+    let config = load_config().map_err(|e| format!("Failed to load config: {}", e))?;
+    let ticktick = &config.ticktick;
+    let tokens =
+        super::refresh_access_token(&TICKTICK, &oauth_client(ticktick), &ticktick.refresh_token)
+            .await?;
+
+    store_tokens(tokens)
+}
+
+/// The configured access token, refreshed first if it's about to expire
+pub async fn get_valid_access_token() -> Result<String, String> {
+    let config = load_config().map_err(|e| format!("Failed to load config: {}", e))?;
+
+    if config.ticktick.access_token.is_empty() {
+        return Err("TickTick access token not configured".to_string());
+    }
+
+    if super::expires_soon(&config.ticktick.token_expiry) {
+        // TickTick doesn't always issue refresh tokens, so an expired token
+        // may need the full flow again
+        if config.ticktick.refresh_token.is_empty() {
+            return Err("TickTick access token expired. Please re-authenticate.".to_string());
+        }
+        return refresh_access_token().await;
+    }
+
+    Ok(config.ticktick.access_token)
+}
@@ -0,0 +1,42 @@
+use std::fs;
+
+/// A single value from a file like an IIO channel, multiplied by `scale`
+pub fn read(path: Option<&str>, scale: f64) -> Result<Option<f64>, String> {
+    let Some(path) = path else {
+        return Ok(None);
+    };
+
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+
+    let raw: f64 = content
+        .trim()
+        .parse()
+        .map_err(|e| format!("Invalid sensor value in {}: {}", path, e))?;
+
+    Ok(Some(raw * scale))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_and_scales_a_value() {
+        let path = std::env::temp_dir().join(format!("inkdash-sensor-{}", std::process::id()));
+        fs::write(&path, "21450\n").unwrap();
+
+        let value = read(path.to_str(), 0.001);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(value, Ok(Some(21.45)));
+    }
+
+    #[test]
+    fn skips_unset_paths_and_reports_missing_files() {
+        assert_eq!(read(None, 1.0), Ok(None));
+
+        let error = read(Some("/nonexistent/inkdash/in_temp_input"), 1.0).unwrap_err();
+        assert!(error.starts_with("Failed to read /nonexistent/inkdash/in_temp_input"));
+    }
+}
@@ -0,0 +1,25 @@
+use super::{parse_json_payload, Measurements};
+use reqwest::Client;
+use std::time::Duration;
+
+pub async fn read(url: &str) -> Result<Measurements, String> {
+    let client = Client::new();
+
+    let response = client
+        .get(url)
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await
+        .map_err(|e| format!("Sensor request failed: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Sensor endpoint error: {}", response.status()));
+    }
+
+    let body = response
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to read sensor response: {}", e))?;
+
+    parse_json_payload(&body)
+}
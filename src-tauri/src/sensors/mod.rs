@@ -0,0 +1,170 @@
+mod file;
+mod http;
+mod mqtt;
+
+use crate::config::{SensorLocation, SensorSource, SensorSourceKind, SensorsConfig};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::task::JoinSet;
+
+/// A reading from a local sensor, shown as "measured" next to the forecast
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SensorReading {
+    pub name: String,
+    pub location: SensorLocation,
+    /// Degrees celsius
+    pub temperature: Option<f64>,
+    /// Relative humidity percentage
+    pub humidity: Option<f64>,
+    /// CO2 in ppm
+    pub co2: Option<f64>,
+    pub last_updated: String,
+}
+
+/// Raw values read from a source, before rounding
+#[derive(Debug, Default)]
+struct Measurements {
+    temperature: Option<f64>,
+    humidity: Option<f64>,
+    co2: Option<f64>,
+}
+
+fn first_number(value: &Value, keys: &[&str]) -> Option<f64> {
+    keys.iter().find_map(|key| match value.get(key) {
+        Some(Value::Number(n)) => n.as_f64(),
+        Some(Value::String(s)) => s.trim().parse().ok(),
+        _ => None,
+    })
+}
+
+/// Parse a JSON payload like `{"temperature": 21.4, "humidity": 40, "co2": 612}`.
+/// A bare number is treated as a temperature
+fn parse_json_payload(payload: &[u8]) -> Result<Measurements, String> {
+    let value: Value = serde_json::from_slice(payload)
+        .map_err(|e| format!("Failed to parse sensor payload: {}", e))?;
+
+    if let Some(temperature) = value.as_f64() {
+        return Ok(Measurements {
+            temperature: Some(temperature),
+            ..Default::default()
+        });
+    }
+
+    let measurements = Measurements {
+        temperature: first_number(&value, &["temperature", "temp"]),
+        humidity: first_number(&value, &["humidity", "hum"]),
+        co2: first_number(&value, &["co2", "eco2"]),
+    };
+
+    if measurements.temperature.is_none()
+        && measurements.humidity.is_none()
+        && measurements.co2.is_none()
+    {
+        return Err("Sensor payload has no temperature, humidity or co2".to_string());
+    }
+
+    Ok(measurements)
+}
+
+fn round_to(value: Option<f64>, decimals: i32) -> Option<f64> {
+    let multiplier = 10_f64.powi(decimals);
+    value.map(|v| (v * multiplier).round() / multiplier)
+}
+
+async fn read_source(source: &SensorSource) -> Result<SensorReading, String> {
+    let measurements = match &source.kind {
+        SensorSourceKind::Mqtt {
+            host,
+            port,
+            topic,
+            username,
+            password,
+        } => mqtt::read(host, *port, topic, username.as_deref(), password.as_deref()).await?,
+        SensorSourceKind::Http { url } => http::read(url).await?,
+        SensorSourceKind::File {
+            temperature_path,
+            humidity_path,
+            co2_path,
+            temperature_scale,
+            humidity_scale,
+            co2_scale,
+        } => Measurements {
+            temperature: file::read(temperature_path.as_deref(), *temperature_scale)?,
+            humidity: file::read(humidity_path.as_deref(), *humidity_scale)?,
+            co2: file::read(co2_path.as_deref(), *co2_scale)?,
+        },
+    };
+
+    Ok(SensorReading {
+        name: source.name.clone(),
+        location: source.location,
+        temperature: round_to(measurements.temperature, 1),
+        humidity: round_to(measurements.humidity, 0),
+        co2: round_to(measurements.co2, 0),
+        last_updated: Local::now().to_rfc3339(),
+    })
+}
+
+/// Read every configured sensor concurrently, keeping the config order and
+/// skipping (and logging) any that fail
+pub async fn fetch_local_sensors(config: &SensorsConfig) -> Vec<SensorReading> {
+    let mut reads = JoinSet::new();
+    for (index, source) in config.sources.iter().enumerate() {
+        let source = source.clone();
+        reads.spawn(async move { (index, read_source(&source).await) });
+    }
+
+    let mut readings: Vec<Option<SensorReading>> = vec![None; config.sources.len()];
+    while let Some(joined) = reads.join_next().await {
+        match joined {
+            Ok((index, Ok(reading))) => readings[index] = Some(reading),
+            Ok((index, Err(e))) => {
+                log::warn!(
+                    "Sensors: Failed to read '{}': {}",
+                    config.sources[index].name,
+                    e
+                )
+            }
+            Err(e) => log::warn!("Sensors: Read task failed: {}", e),
+        }
+    }
+
+    readings.into_iter().flatten().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_object_payloads() {
+        let m =
+            parse_json_payload(br#"{"temperature": 21.4, "humidity": 40, "co2": 612}"#).unwrap();
+        assert_eq!(m.temperature, Some(21.4));
+        assert_eq!(m.humidity, Some(40.0));
+        assert_eq!(m.co2, Some(612.0));
+    }
+
+    #[test]
+    fn accepts_short_keys_and_string_numbers() {
+        let m = parse_json_payload(br#"{"temp": "19.5", "hum": " 55 ", "battery": 90}"#).unwrap();
+        assert_eq!(m.temperature, Some(19.5));
+        assert_eq!(m.humidity, Some(55.0));
+        assert_eq!(m.co2, None);
+    }
+
+    #[test]
+    fn treats_a_bare_number_as_temperature() {
+        let m = parse_json_payload(b"-3.25").unwrap();
+        assert_eq!(m.temperature, Some(-3.25));
+        assert_eq!(m.humidity, None);
+    }
+
+    #[test]
+    fn rejects_payloads_without_readings() {
+        assert!(parse_json_payload(br#"{"battery": 90}"#).is_err());
+        assert!(parse_json_payload(b"not json").is_err());
+    }
+}
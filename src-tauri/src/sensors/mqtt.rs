@@ -0,0 +1,59 @@
+use super::{parse_json_payload, Measurements};
+use rumqttc::{AsyncClient, Event, MqttOptions, Packet, QoS};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+// Sensors usually publish retained messages, so this only needs to cover
+// the connect/subscribe round trip. Kept short since the weather fetch waits on it
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Brokers drop an existing session when a client id reconnects, so every
+// connection gets its own
+static CONNECTION_COUNT: AtomicU64 = AtomicU64::new(0);
+
+pub async fn read(
+    host: &str,
+    port: u16,
+    topic: &str,
+    username: Option<&str>,
+    password: Option<&str>,
+) -> Result<Measurements, String> {
+    let client_id = format!(
+        "inkdash-{}-{}",
+        std::process::id(),
+        CONNECTION_COUNT.fetch_add(1, Ordering::Relaxed)
+    );
+    let mut options = MqttOptions::new(client_id, host, port);
+    options.set_keep_alive(Duration::from_secs(5));
+    if let (Some(username), Some(password)) = (username, password) {
+        options.set_credentials(username, password);
+    }
+
+    let (client, mut eventloop) = AsyncClient::new(options, 10);
+    client
+        .subscribe(topic, QoS::AtMostOnce)
+        .await
+        .map_err(|e| format!("MQTT subscribe failed: {}", e))?;
+
+    let payload = tokio::time::timeout(READ_TIMEOUT, async {
+        loop {
+            match eventloop.poll().await {
+                Ok(Event::Incoming(Packet::Publish(publish))) => return Ok(publish.payload),
+                Ok(_) => {}
+                Err(e) => return Err(format!("MQTT connection failed: {}", e)),
+            }
+        }
+    })
+    .await
+    .map_err(|_| {
+        format!(
+            "No MQTT message on '{}' within {}s",
+            topic,
+            READ_TIMEOUT.as_secs()
+        )
+    })??;
+
+    let _ = client.disconnect().await;
+
+    parse_json_payload(&payload)
+}
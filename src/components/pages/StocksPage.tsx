@@ -9,7 +9,7 @@ import { Card } from '@/components/ui/card';
  * Clean 2x2 grid with generous spacing.
  */
 export function StocksPage() {
-  const { stocks, isLoading, error } = useStocks();
+  const { stocks, unavailable, alerts, dismissAlert, isLoading, error } = useStocks();
 
   // Loading skeleton
   if (isLoading && stocks.length === 0) {
@@ -50,12 +50,41 @@ export function StocksPage() {
 
   return (
     <div className="h-full w-full page-padding flex flex-col overflow-hidden">
+      {/* Alerts fired by the backend, tap to dismiss */}
+      {alerts.length > 0 && (
+        <div className="flex flex-col gap-2 w-full max-w-3xl mx-auto mb-4">
+          {alerts.map((alert) => (
+            <button
+              key={`${alert.ticker}-${alert.triggeredAt}-${alert.condition.kind}`}
+              onClick={() => dismissAlert(alert)}
+              className="text-left text-sm text-foreground bg-card border rounded-lg px-4 py-2"
+            >
+              {alert.message}
+            </button>
+          ))}
+        </div>
+      )}
+
       {/* Full-height centered grid */}
       <div className="flex-1 flex items-center justify-center min-h-0">
         <div className="grid grid-cols-2 gap-4 w-full max-w-3xl h-full max-h-[420px]">
           {stocks.map((stock) => (
             <StockCard key={stock.ticker} stock={stock} />
           ))}
+          {unavailable.map((status) => (
+            <Card key={status.ticker} className="h-full">
+              <div className="h-full flex flex-col items-center justify-center p-4 text-center">
+                <span className="text-lg font-medium-labels text-foreground">{status.ticker}</span>
+                <span className="text-xs text-muted-foreground mt-1">
+                  {status.status === 'timeout'
+                    ? 'Timed out'
+                    : status.status === 'not-found'
+                      ? 'Unknown symbol'
+                      : 'Unavailable'}
+                </span>
+              </div>
+            </Card>
+          ))}
         </div>
       </div>
     </div>
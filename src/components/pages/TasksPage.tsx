@@ -1,19 +1,76 @@
+import { useMemo } from 'react'
 import { useTickTick } from '@/hooks/useTickTick'
+import { useTaskProvider, useTasks } from '@/hooks/useTasks'
 import { TaskList } from '@/components/widgets/TaskList'
 import { Card, CardContent } from '@/components/ui/card'
 import { Button } from '@/components/ui/button'
 import { RefreshCw } from 'lucide-react'
+import type { Task, TickTickTask } from '@/types'
+
+const PROVIDER_NAMES = {
+  ticktick: 'TickTick',
+  caldav: 'CalDAV',
+  todoist: 'Todoist',
+  file: 'the task file',
+} as const
+
+function LoadingTasks() {
+  return (
+    <div className="h-full w-full page-padding flex items-center justify-center">
+      <div className="flex flex-col items-center gap-3">
+        <div className="h-8 w-8 animate-spin rounded-full border-4 border-muted border-t-foreground" />
+        <p className="text-sm text-muted-foreground">Loading tasks...</p>
+      </div>
+    </div>
+  )
+}
+
+/** A provider task in the shape the task list widgets display */
+function toListTask(task: Task): TickTickTask {
+  return {
+    id: task.id,
+    title: task.title,
+    isCompleted: task.isCompleted,
+    priority: task.priority,
+    dueDate: task.dueDate,
+    dueDay: task.isAllDay ? task.dueDate : undefined,
+    isAllDay: task.isAllDay,
+    projectId: task.listId,
+    projectName: task.listName,
+    tags: task.tags,
+    notes: task.notes,
+    createdTime: '',
+    modifiedTime: task.modifiedTime ?? '',
+    checklist: [],
+    reminderOffsets: [],
+  }
+}
 
 /**
- * TasksPage - Read-only TickTick task viewer
+ * TasksPage - task list from the provider set in the config's `[tasks]` section
  *
- * Displays tasks from TickTick API with filtering options:
- * - Today: Tasks due today or overdue
- * - This Week: Tasks due within the next 7 days
- * - Backlog: Tasks without a due date
+ * TickTick gets the full page with offline changes and completion stats;
+ * CalDAV, Todoist and task files show the same list with completion only
  */
 export function TasksPage() {
-  const { data, isLoading, isRefreshing, error, refresh } = useTickTick()
+  const provider = useTaskProvider()
+
+  if (!provider) return <LoadingTasks />
+  return provider === 'ticktick' ? <TickTickTasks /> : <ProviderTasks />
+}
+
+/**
+ * Tasks from CalDAV, Todoist or a task file
+ */
+function ProviderTasks() {
+  const { data, isLoading, isRefreshing, error, actionError, refresh, completeTask } = useTasks()
+
+  const tasks = useMemo(() => data?.tasks.map(toListTask) ?? [], [data])
+
+  const handleComplete = (listTask: TickTickTask) => {
+    const task = data?.tasks.find(t => t.id === listTask.id)
+    if (task) completeTask(task)
+  }
 
   if (error) {
     return (
@@ -26,7 +83,7 @@ export function TasksPage() {
               </h2>
               <p className="text-sm text-muted-foreground mb-4">{error}</p>
               <p className="text-xs text-muted-foreground">
-                Please check your .env configuration and ensure your TickTick API credentials are set correctly.
+                Check the [tasks] section of the config file.
               </p>
             </div>
           </CardContent>
@@ -35,17 +92,96 @@ export function TasksPage() {
     )
   }
 
-  if (isLoading) {
+  if (isLoading || !data) return <LoadingTasks />
+
+  return (
+    <div className="h-full w-full page-padding flex flex-col overflow-hidden">
+      <div className="flex items-center justify-end gap-3 mb-2">
+        {actionError && (
+          <p className="text-xs text-red-500 truncate">{actionError}</p>
+        )}
+        <Button
+          variant="ghost"
+          size="icon"
+          onClick={refresh}
+          disabled={isRefreshing}
+          aria-label="Refresh tasks"
+        >
+          <RefreshCw className={`w-5 h-5 ${isRefreshing ? 'animate-spin' : ''}`} />
+        </Button>
+      </div>
+      <Card className="flex-1 min-h-0">
+        <CardContent className="h-full py-6">
+          {tasks.length === 0 ? (
+            <div className="flex items-center justify-center h-full text-muted-foreground text-sm">
+              You don't have any active tasks in {PROVIDER_NAMES[data.provider]}.
+            </div>
+          ) : (
+            <TaskList tasks={tasks} onComplete={handleComplete} />
+          )}
+        </CardContent>
+      </Card>
+    </div>
+  )
+}
+
+/**
+ * Tasks from TickTick, with filtering options:
+ * - Today: Tasks due today or overdue
+ * - This Week: Tasks due within the next 7 days
+ * - Backlog: Tasks without a due date
+ *
+ * Checking a task off completes it in TickTick. Changes made offline are
+ * queued and shown as pending until they sync
+ */
+function TickTickTasks() {
+  const {
+    data,
+    isLoading,
+    isRefreshing,
+    error,
+    refresh,
+    completionStats,
+    changedTaskIds,
+    actionError,
+    pendingActions,
+    offline,
+    discardAction,
+    completeTask,
+    startOAuth,
+  } = useTickTick()
+
+  const failedActions = pendingActions.filter(a => a.error)
+  const queuedCount = pendingActions.length - failedActions.length
+
+  if (error) {
     return (
       <div className="h-full w-full page-padding flex items-center justify-center">
-        <div className="flex flex-col items-center gap-3">
-          <div className="h-8 w-8 animate-spin rounded-full border-4 border-muted border-t-foreground" />
-          <p className="text-sm text-muted-foreground">Loading tasks...</p>
-        </div>
+        <Card className="max-w-md">
+          <CardContent className="pt-6">
+            <div className="text-center">
+              <h2 className="text-lg font-semibold text-red-500 mb-2">
+                Failed to load tasks
+              </h2>
+              <p className="text-sm text-muted-foreground mb-4">{error}</p>
+              <p className="text-xs text-muted-foreground mb-4">
+                Set your TickTick client_id and client_secret in the config file, then connect.
+              </p>
+              <button
+                onClick={startOAuth}
+                className="w-full px-4 py-2 bg-primary text-primary-foreground rounded-lg hover:opacity-90 transition-opacity"
+              >
+                Connect TickTick
+              </button>
+            </div>
+          </CardContent>
+        </Card>
       </div>
     )
   }
 
+  if (isLoading) return <LoadingTasks />
+
   if (!data || data.tasks.length === 0) {
     return (
       <div className="h-full w-full page-padding flex items-center justify-center">
@@ -65,7 +201,41 @@ export function TasksPage() {
 
   return (
     <div className="h-full w-full page-padding flex flex-col overflow-hidden">
-      <div className="flex justify-end mb-2">
+      <div className="flex items-center justify-end gap-3 mb-2">
+        {completionStats && (
+          <p className="mr-auto text-sm text-muted-foreground tabular-nums">
+            Done today: <span className="font-medium text-foreground">{completionStats.today}</span>
+            {completionStats.streakDays > 1 && (
+              <> · {completionStats.streakDays}-day streak</>
+            )}
+          </p>
+        )}
+        {actionError && (
+          <p className="text-xs text-red-500 truncate">{actionError}</p>
+        )}
+        {(offline || queuedCount > 0) && (
+          <p className="text-xs text-muted-foreground truncate">
+            {offline ? 'Offline' : 'Syncing'}
+            {queuedCount > 0 && ` · ${queuedCount} change${queuedCount === 1 ? '' : 's'} waiting to sync`}
+          </p>
+        )}
+        {failedActions.length > 0 && (
+          <button
+            className="text-xs text-red-500 truncate hover:underline"
+            title={failedActions.map(a => a.error).join('\n')}
+            onClick={() => failedActions.forEach(a => discardAction(a.id))}
+          >
+            {failedActions.length} change{failedActions.length === 1 ? '' : 's'} not synced · Dismiss
+          </button>
+        )}
+        {data.failedProjects.length > 0 && (
+          <p
+            className="text-xs text-muted-foreground truncate"
+            title={data.failedProjects.map(p => `${p.projectName}: ${p.error}`).join('\n')}
+          >
+            Couldn't load {data.failedProjects.map(p => p.projectName).join(', ')}
+          </p>
+        )}
         <Button
           variant="ghost"
           size="icon"
@@ -78,7 +248,7 @@ export function TasksPage() {
       </div>
       <Card className="flex-1 min-h-0">
         <CardContent className="h-full py-6">
-          <TaskList tasks={data.tasks} />
+          <TaskList tasks={data.tasks} onComplete={completeTask} changedTaskIds={changedTaskIds} />
         </CardContent>
       </Card>
     </div>
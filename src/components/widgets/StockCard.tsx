@@ -114,6 +114,14 @@ export function StockCard({ stock }: StockCardProps) {
   // Format price with currency using priceHint
   const priceText = `${stock.currency}${price.toFixed(decimals)}`;
 
+  // Pre/post-market price, or a closed marker so the change isn't read as today's move
+  const extended = stock.extendedHours;
+  const sessionText = extended
+    ? `${extended.session === 'pre' ? 'Pre' : 'After hours'} ${stock.currency}${extended.price.toFixed(decimals)} (${extended.changePercent >= 0 ? '+' : ''}${extended.changePercent.toFixed(2)}%)`
+    : stock.market?.state === 'closed'
+      ? 'Market closed'
+      : null;
+
   return (
     <Card className="h-full relative overflow-hidden">
       {/* Chart as background - the hero element */}
@@ -157,6 +165,14 @@ export function StockCard({ stock }: StockCardProps) {
             >
               {changeText} <span className="text-xs">({isPositive ? '↑' : '↓'}{Math.abs(change).toFixed(decimals)})</span>
             </div>
+            {sessionText && (
+              <div
+                className="text-[11px] text-muted-foreground mt-0.5"
+                style={{ textShadow: '0 1px 6px hsl(var(--card))' }}
+              >
+                {sessionText}
+              </div>
+            )}
           </div>
         </div>
       </div>
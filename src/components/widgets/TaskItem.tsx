@@ -1,15 +1,21 @@
 import type { TickTickTask } from '@/types'
-import { cn } from '@/lib/utils'
+import { Repeat } from 'lucide-react'
+import { Checkbox } from '@/components/ui/checkbox'
+import { cn, taskDueDate } from '@/lib/utils'
 
 interface TaskItemProps {
   task: TickTickTask
+  onComplete?: (task: TickTickTask) => void
+  /** Added or edited elsewhere since the last sync */
+  isChanged?: boolean
 }
 
 /**
  * Individual task item component
- * Displays task title, priority, due date, and project
+ * Displays task title, priority, due date and time, checklist progress, and project,
+ * with a checkbox to complete it
  */
-export function TaskItem({ task }: TaskItemProps) {
+export function TaskItem({ task, onComplete, isChanged }: TaskItemProps) {
   // Priority color mapping
   const priorityColors = {
     0: 'text-muted-foreground', // none
@@ -20,22 +26,26 @@ export function TaskItem({ task }: TaskItemProps) {
 
   const priorityColor = priorityColors[task.priority as keyof typeof priorityColors] || priorityColors[0]
 
-  // Format due date
-  const formatDueDate = (dateStr?: string) => {
-    if (!dateStr) return null
-    const date = new Date(dateStr)
+  // Format due date, with the time for tasks that aren't all-day
+  const formatDueDate = () => {
+    const date = taskDueDate(task)
+    if (!date) return null
     const today = new Date()
     const tomorrow = new Date(today)
     tomorrow.setDate(tomorrow.getDate() + 1)
 
+    const time = task.isAllDay
+      ? ''
+      : ` ${date.toLocaleTimeString('en-US', { hour: 'numeric', minute: '2-digit' })}`
+
     // Check if it's today
     if (date.toDateString() === today.toDateString()) {
-      return { text: 'Today', isOverdue: false, isToday: true }
+      return { text: `Today${time}`, isOverdue: !task.isAllDay && date < today, isToday: true }
     }
 
     // Check if it's tomorrow
     if (date.toDateString() === tomorrow.toDateString()) {
-      return { text: 'Tomorrow', isOverdue: false, isToday: false }
+      return { text: `Tomorrow${time}`, isOverdue: false, isToday: false }
     }
 
     // Check if overdue
@@ -48,13 +58,25 @@ export function TaskItem({ task }: TaskItemProps) {
       day: 'numeric',
     })
 
-    return { text: formattedDate, isOverdue, isToday: false }
+    return { text: `${formattedDate}${time}`, isOverdue, isToday: false }
   }
 
-  const dueInfo = formatDueDate(task.dueDate)
+  const dueInfo = formatDueDate()
 
   return (
-    <div className="group flex items-start gap-3 py-3 px-4 hover:bg-muted/30 rounded-lg transition-colors">
+    <div className={cn(
+      "group flex items-start gap-3 py-3 px-4 hover:bg-muted/30 rounded-lg transition-colors",
+      isChanged && "animate-slide-up"
+    )}>
+      {onComplete && (
+        <Checkbox
+          className="mt-0.5 size-5"
+          checked={task.isCompleted}
+          onCheckedChange={checked => checked === true && onComplete(task)}
+          aria-label={`Complete ${task.title}`}
+        />
+      )}
+
       {/* Priority indicator dot */}
       <div className={cn("mt-1.5 h-2 w-2 rounded-full flex-shrink-0", priorityColor)} />
 
@@ -82,6 +104,19 @@ export function TaskItem({ task }: TaskItemProps) {
             </>
           )}
 
+          {/* Repeating task */}
+          {task.recurrence && <Repeat className="h-3 w-3 flex-shrink-0" aria-label="Repeats" />}
+
+          {/* Checklist progress */}
+          {task.checklistProgress && (
+            <>
+              <span>•</span>
+              <span className="tabular-nums">
+                {task.checklistProgress.completed}/{task.checklistProgress.total} done
+              </span>
+            </>
+          )}
+
           {/* Tags */}
           {task.tags && task.tags.length > 0 && (
             <>
@@ -1,18 +1,20 @@
 import { useState, useMemo } from 'react'
 import type { TickTickTask, TaskFilter } from '@/types'
 import { TaskItem } from './TaskItem'
-import { cn } from '@/lib/utils'
+import { cn, taskDueDate } from '@/lib/utils'
 
 interface TaskListProps {
   tasks: TickTickTask[]
   projectName?: string
+  onComplete?: (task: TickTickTask) => void
+  changedTaskIds?: Set<string>
 }
 
 /**
  * Task list component with filtering
  * Supports filtering by: today, this week, backlog
  */
-export function TaskList({ tasks, projectName }: TaskListProps) {
+export function TaskList({ tasks, projectName, onComplete, changedTaskIds }: TaskListProps) {
   const [activeFilter, setActiveFilter] = useState<TaskFilter>('today')
 
   // Filter tasks based on selected filter
@@ -26,8 +28,8 @@ export function TaskList({ tasks, projectName }: TaskListProps) {
       case 'today': {
         // Tasks due today or overdue
         return tasks.filter(task => {
-          if (!task.dueDate) return false
-          const dueDate = new Date(task.dueDate)
+          const dueDate = taskDueDate(task)
+          if (!dueDate) return false
           const dueDateStart = new Date(dueDate.getFullYear(), dueDate.getMonth(), dueDate.getDate())
           return dueDateStart <= today
         })
@@ -35,8 +37,8 @@ export function TaskList({ tasks, projectName }: TaskListProps) {
       case 'week': {
         // Tasks due within the next 7 days
         return tasks.filter(task => {
-          if (!task.dueDate) return false
-          const dueDate = new Date(task.dueDate)
+          const dueDate = taskDueDate(task)
+          if (!dueDate) return false
           const dueDateStart = new Date(dueDate.getFullYear(), dueDate.getMonth(), dueDate.getDate())
           return dueDateStart >= today && dueDateStart < weekFromNow
         })
@@ -58,8 +60,10 @@ export function TaskList({ tasks, projectName }: TaskListProps) {
         return b.priority - a.priority
       }
       // Then by due date (earlier first)
-      if (a.dueDate && b.dueDate) {
-        return new Date(a.dueDate).getTime() - new Date(b.dueDate).getTime()
+      const aDue = taskDueDate(a)
+      const bDue = taskDueDate(b)
+      if (aDue && bDue) {
+        return aDue.getTime() - bDue.getTime()
       }
       if (a.dueDate) return -1
       if (b.dueDate) return 1
@@ -97,8 +101,8 @@ export function TaskList({ tasks, projectName }: TaskListProps) {
                 ({
                   filter.value === 'today'
                     ? tasks.filter(t => {
-                        if (!t.dueDate) return false
-                        const dueDate = new Date(t.dueDate)
+                        const dueDate = taskDueDate(t)
+                        if (!dueDate) return false
                         const today = new Date()
                         const dueDateStart = new Date(dueDate.getFullYear(), dueDate.getMonth(), dueDate.getDate())
                         const todayStart = new Date(today.getFullYear(), today.getMonth(), today.getDate())
@@ -106,8 +110,8 @@ export function TaskList({ tasks, projectName }: TaskListProps) {
                       }).length
                     : filter.value === 'week'
                     ? tasks.filter(t => {
-                        if (!t.dueDate) return false
-                        const dueDate = new Date(t.dueDate)
+                        const dueDate = taskDueDate(t)
+                        if (!dueDate) return false
                         const today = new Date()
                         const todayStart = new Date(today.getFullYear(), today.getMonth(), today.getDate())
                         const weekFromNow = new Date(todayStart)
@@ -132,7 +136,13 @@ export function TaskList({ tasks, projectName }: TaskListProps) {
         ) : (
           <div className="space-y-0.5">
             {sortedTasks.map(task => (
-              <TaskItem key={task.id} task={task} />
+              <TaskItem
+                // Remount edited tasks so their highlight plays again
+                key={`${task.id}:${task.modifiedTime}`}
+                task={task}
+                onComplete={onComplete}
+                isChanged={changedTaskIds?.has(task.id)}
+              />
             ))}
           </div>
         )}
@@ -73,9 +73,10 @@ export function WeatherWidget() {
     return `${displayHour}:${minutes.toString().padStart(2, '0')}${ampm}`;
   };
 
-  // Prepare chart data - every 2 hours from 12am to 10pm (12 bars)
+  // Prepare chart data - every 2 hours from 12am to 10pm (up to 12 bars, some
+  // providers leave out hours that have already passed)
   const chartData = weather.hourlyForecast
-    .filter((_, index) => index % 2 === 0)
+    .filter((hourData) => hourData.hour % 2 === 0)
     .map((hourData) => ({
       hour: hourData.hour,
       label: formatHour(hourData.hour),
@@ -85,16 +86,22 @@ export function WeatherWidget() {
       isCurrent: hourData.hour === currentHour || hourData.hour === currentHour - 1,
     }));
 
-  // Condition display text (capitalize each word, replace hyphens with spaces)
-  const conditionText = weather.condition
-    .split('-')
-    .map((word) => word.charAt(0).toUpperCase() + word.slice(1))
-    .join(' ');
+  // Condition display text (e.g., "Light freezing rain")
+  const conditionText = weather.condition.description;
 
   // Feels like temperature with unit and degree symbol
   const unit = weather.unit === 'celsius' ? 'C' : 'F';
   const feelsLikeText = `${weather.feelsLike}°${unit}`;
 
+  // Air quality summary (e.g., "AQI 42 · Good")
+  const airQualityText =
+    weather.airQuality?.aqi != null
+      ? `AQI ${weather.airQuality.aqi} · ${weather.airQuality.aqiCategory
+          .split('-')
+          .map((word) => word.charAt(0).toUpperCase() + word.slice(1))
+          .join(' ')}`
+      : null;
+
   // Get fill color for each bar
   const getBarFill = (entry: typeof chartData[0]) => {
     if (entry.isCurrent) {
@@ -119,6 +126,13 @@ export function WeatherWidget() {
           Feels like {feelsLikeText}
         </div>
 
+        {/* Air quality */}
+        {airQualityText && (
+          <div className="text-xs text-muted-foreground text-center">
+            {airQualityText}
+          </div>
+        )}
+
         {/* Hourly bar chart - all 24 hours */}
         <div className="h-48 mt-1 mb-1">
           <ResponsiveContainer width="100%" height="100%">
@@ -1,16 +1,23 @@
 import { useState, useEffect, useCallback } from 'react'
 import { invoke } from '@tauri-apps/api/core'
-import type { StockData } from '@/types'
+import { listen } from '@tauri-apps/api/event'
+import type { StockAlert, StockData, StocksData, TickerStatus } from '@/types'
 
 interface UseStocksReturn {
   stocks: StockData[]
+  /** Tickers that could not be fetched on the last refresh */
+  unavailable: TickerStatus[]
+  /** Alerts fired since the app started, newest first */
+  alerts: StockAlert[]
+  dismissAlert: (alert: StockAlert) => void
   isLoading: boolean
   error: string | null
   lastUpdated: Date | null
   refresh: () => Promise<void>
 }
 
-const REFRESH_INTERVAL = 5 * 60 * 1000 // 5 minutes
+const DEFAULT_REFRESH_MINUTES = 5
+const MAX_ALERTS = 5
 
 // Transform the response from Tauri (dates come as strings)
 function transformStockData(data: (StockData & { lastUpdated: string })[]): StockData[] {
@@ -23,17 +30,39 @@ function transformStockData(data: (StockData & { lastUpdated: string })[]): Stoc
 /**
  * Hook for fetching and managing stock data
  * - Fetches from Yahoo Finance API via Tauri backend
- * - Auto-refreshes every 5 minutes
+ * - Auto-refreshes on the interval suggested by the backend, which slows
+ *   down while every market is closed
  */
 export function useStocks(): UseStocksReturn {
   const [stocks, setStocks] = useState<StockData[]>([])
+  const [unavailable, setUnavailable] = useState<TickerStatus[]>([])
   const [isLoading, setIsLoading] = useState(true)
   const [error, setError] = useState<string | null>(null)
 
+  const [refreshMinutes, setRefreshMinutes] = useState(DEFAULT_REFRESH_MINUTES)
+  const [alerts, setAlerts] = useState<StockAlert[]>([])
+
+  useEffect(() => {
+    const unlisten = listen<StockAlert>('stock-alert', (event) => {
+      setAlerts((current) => [event.payload, ...current].slice(0, MAX_ALERTS))
+    })
+    return () => {
+      unlisten.then((fn) => fn())
+    }
+  }, [])
+
+  const dismissAlert = useCallback((alert: StockAlert) => {
+    setAlerts((current) => current.filter((a) => a !== alert))
+  }, [])
+
   const refresh = useCallback(async () => {
     try {
-      const stockData = await invoke<(StockData & { lastUpdated: string })[]>('fetch_stocks')
-      setStocks(transformStockData(stockData))
+      const stocksData = await invoke<
+        Omit<StocksData, 'stocks'> & { stocks: (StockData & { lastUpdated: string })[] }
+      >('fetch_stocks')
+      setStocks(transformStockData(stocksData.stocks))
+      setUnavailable(stocksData.statuses.filter((status) => status.status !== 'ok'))
+      setRefreshMinutes(stocksData.refreshIntervalMinutes || DEFAULT_REFRESH_MINUTES)
       setError(null)
     } catch (err) {
       const message = err instanceof Error ? err.message : String(err)
@@ -49,10 +78,12 @@ export function useStocks(): UseStocksReturn {
     }
 
     initialFetch()
+  }, [refresh])
 
-    const intervalId = setInterval(refresh, REFRESH_INTERVAL)
+  useEffect(() => {
+    const intervalId = setInterval(refresh, refreshMinutes * 60 * 1000)
     return () => clearInterval(intervalId)
-  }, [refresh])
+  }, [refresh, refreshMinutes])
 
   const lastUpdated =
     stocks.length > 0
@@ -64,6 +95,9 @@ export function useStocks(): UseStocksReturn {
 
   return {
     stocks,
+    unavailable,
+    alerts,
+    dismissAlert,
     isLoading,
     error,
     lastUpdated,
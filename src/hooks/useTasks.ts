@@ -0,0 +1,122 @@
+import { useState, useEffect, useCallback, useRef } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import type { Task, TaskProviderKind, TasksData } from '@/types'
+
+interface TasksConfig {
+  tasks: { provider: TaskProviderKind }
+}
+
+interface RefreshIntervals {
+  ticktick_minutes: number
+  calendar_minutes: number
+}
+
+interface UseTasksReturn {
+  data: TasksData | null
+  isLoading: boolean
+  isRefreshing: boolean
+  error: string | null
+  /** Error from the last completion, after it was rolled back */
+  actionError: string | null
+  refresh: () => Promise<void>
+  completeTask: (task: Task) => Promise<void>
+}
+
+const DEFAULT_REFRESH_INTERVAL = 15 * 60 * 1000 // 15 minutes
+
+function errorMessage(err: unknown): string {
+  return err instanceof Error ? err.message : String(err)
+}
+
+/**
+ * The task provider set in the config's `[tasks]` section, or null until it's loaded.
+ * TickTick keeps its own hook with offline changes and stats; the others go through useTasks
+ */
+export function useTaskProvider(): TaskProviderKind | null {
+  const [provider, setProvider] = useState<TaskProviderKind | null>(null)
+
+  useEffect(() => {
+    invoke<TasksConfig>('get_config')
+      .then(config => setProvider(config.tasks.provider))
+      .catch(() => setProvider('ticktick'))
+  }, [])
+
+  return provider
+}
+
+/**
+ * Hook for tasks from a CalDAV, Todoist or task file provider
+ * - Fetches via the backend's provider-neutral `fetch_tasks`
+ * - Auto-refreshes on the TickTick interval from the config (default: 15 minutes)
+ * - Completes tasks optimistically, rolling back on failure
+ */
+export function useTasks(): UseTasksReturn {
+  const [data, setData] = useState<TasksData | null>(null)
+  const [isLoading, setIsLoading] = useState(true)
+  const [isRefreshing, setIsRefreshing] = useState(false)
+  const [error, setError] = useState<string | null>(null)
+  const [actionError, setActionError] = useState<string | null>(null)
+  const intervalRef = useRef<ReturnType<typeof setInterval> | null>(null)
+
+  const refresh = useCallback(async (showRefreshing = true) => {
+    if (showRefreshing) setIsRefreshing(true)
+    try {
+      setData(await invoke<TasksData>('fetch_tasks'))
+      setError(null)
+    } catch (err) {
+      setError(errorMessage(err))
+    } finally {
+      if (showRefreshing) setIsRefreshing(false)
+    }
+  }, [])
+
+  const updateTasks = useCallback((change: (tasks: Task[]) => Task[]) => {
+    setData(current => (current ? { ...current, tasks: change(current.tasks) } : current))
+  }, [])
+
+  const completeTask = useCallback(async (task: Task) => {
+    updateTasks(tasks => tasks.filter(t => t.id !== task.id))
+    try {
+      await invoke('complete_provider_task', { listId: task.listId, taskId: task.id })
+      setActionError(null)
+    } catch (err) {
+      updateTasks(tasks => (tasks.some(t => t.id === task.id) ? tasks : [...tasks, task]))
+      setActionError(errorMessage(err))
+    }
+  }, [updateTasks])
+
+  useEffect(() => {
+    const setup = async () => {
+      setIsLoading(true)
+
+      let intervalMs = DEFAULT_REFRESH_INTERVAL
+      try {
+        const intervals = await invoke<RefreshIntervals>('get_refresh_intervals')
+        intervalMs = intervals.ticktick_minutes * 60 * 1000
+      } catch {
+        // Use default if config fetch fails
+      }
+
+      await refresh(false)
+      setIsLoading(false)
+
+      intervalRef.current = setInterval(() => refresh(false), intervalMs)
+    }
+
+    setup()
+
+    return () => {
+      if (intervalRef.current) clearInterval(intervalRef.current)
+    }
+  }, [refresh])
+
+  return {
+    data,
+    isLoading,
+    isRefreshing,
+    error,
+    actionError,
+    refresh: () => refresh(true),
+    completeTask,
+  }
+}
@@ -1,6 +1,16 @@
-import { useState, useEffect, useCallback, useRef } from 'react'
+import { useState, useEffect, useCallback, useMemo, useRef } from 'react'
 import { invoke } from '@tauri-apps/api/core'
-import type { TickTickData } from '@/types'
+import type {
+  CompletedTasks,
+  CompletionStats,
+  NewTickTickTask,
+  PendingTaskAction,
+  TaskGrouping,
+  TaskQueryResult,
+  TickTickData,
+  TickTickTask,
+  TickTickTaskUpdate,
+} from '@/types'
 
 interface UseTickTickReturn {
   data: TickTickData | null
@@ -9,6 +19,30 @@ interface UseTickTickReturn {
   error: string | null
   lastUpdated: Date | null
   refresh: () => Promise<void>
+  /** Completion counts and streak, from tasks completed here or seen completed in a sync */
+  completionStats: CompletionStats | null
+  /** Tasks added or changed remotely in the last sync, for highlighting */
+  changedTaskIds: Set<string>
+  /** Error from the last complete/create/update, after it was rolled back */
+  actionError: string | null
+  /** Changes queued while offline, and ones TickTick rejected */
+  pendingActions: PendingTaskAction[]
+  /** Showing the last sync because TickTick couldn't be reached */
+  offline: boolean
+  /** Drop a queued or failed change without sending it */
+  discardAction: (id: string) => Promise<void>
+  completeTask: (task: TickTickTask) => Promise<void>
+  createTask: (task: NewTickTickTask) => Promise<void>
+  updateTask: (update: TickTickTaskUpdate) => Promise<void>
+  startOAuth: () => Promise<void>
+  /**
+   * Run a task query such as `due:today priority>=3`, or a smart list from the config
+   * with `{ list: 'Urgent' }`
+   */
+  queryTasks: (
+    query: string | { list: string },
+    groupBy?: TaskGrouping
+  ) => Promise<TaskQueryResult>
 }
 
 interface RefreshIntervals {
@@ -26,34 +60,188 @@ function transformTickTickData(data: TickTickData & { lastUpdated: string }): Ti
   }
 }
 
+function errorMessage(err: unknown): string {
+  return err instanceof Error ? err.message : String(err)
+}
+
 /**
  * Hook for fetching and managing TickTick tasks
  * - Fetches from TickTick API via Tauri backend
  * - Auto-refreshes based on config (default: 15 minutes)
+ * - Completes, creates and edits tasks optimistically, rolling back on failure
+ * - Changes made offline are queued by the backend and replayed when the network returns
+ * - Handles the TickTick OAuth flow
  */
 export function useTickTick(): UseTickTickReturn {
   const [data, setData] = useState<TickTickData | null>(null)
   const [isLoading, setIsLoading] = useState(true)
   const [isRefreshing, setIsRefreshing] = useState(false)
   const [error, setError] = useState<string | null>(null)
+  const [actionError, setActionError] = useState<string | null>(null)
+  const [completionStats, setCompletionStats] = useState<CompletionStats | null>(null)
+  const [pendingActions, setPendingActions] = useState<PendingTaskAction[]>([])
   const intervalRef = useRef<ReturnType<typeof setInterval> | null>(null)
 
+  const refreshCompletionStats = useCallback(async () => {
+    try {
+      const completed = await invoke<CompletedTasks>('get_completed_tasks', { range: 'week' })
+      setCompletionStats(completed.stats)
+    } catch {
+      // Stats are optional, keep the last ones
+    }
+  }, [])
+
+  const refreshPendingActions = useCallback(async () => {
+    try {
+      setPendingActions(await invoke<PendingTaskAction[]>('get_pending_task_actions'))
+    } catch {
+      // Keep the last known queue
+    }
+  }, [])
+
   const refresh = useCallback(async (showRefreshing = true) => {
     if (showRefreshing) setIsRefreshing(true)
     try {
+      // Manual refreshes skip the backend's local store and always hit the API
       const tickTickData = await invoke<TickTickData & { lastUpdated: string }>(
-        'fetch_ticktick_tasks'
+        'fetch_ticktick_tasks',
+        { force: showRefreshing }
       )
       setData(transformTickTickData(tickTickData))
+      setPendingActions(tickTickData.pendingActions)
       setError(null)
+      // A sync may have found tasks completed elsewhere
+      await refreshCompletionStats()
     } catch (err) {
       const message = err instanceof Error ? err.message : String(err)
       setError(message)
     } finally {
       if (showRefreshing) setIsRefreshing(false)
     }
+  }, [refreshCompletionStats])
+
+  const changedTaskIds = useMemo(
+    () => new Set([...(data?.changes.added ?? []), ...(data?.changes.updated ?? [])].map(t => t.id)),
+    [data?.changes]
+  )
+
+  // Apply a change to the task list, if it's loaded
+  const updateTasks = useCallback((change: (tasks: TickTickTask[]) => TickTickTask[]) => {
+    setData(current => (current ? { ...current, tasks: change(current.tasks) } : current))
   }, [])
 
+  const projectName = useCallback(
+    (projectId: string) => data?.projects.find(p => p.id === projectId)?.name,
+    [data]
+  )
+
+  const completeTask = useCallback(async (task: TickTickTask) => {
+    // The list only holds open tasks, so completing one removes it
+    updateTasks(tasks => tasks.filter(t => t.id !== task.id))
+    try {
+      await invoke('complete_task', { projectId: task.projectId, taskId: task.id })
+      setActionError(null)
+      await Promise.all([refreshCompletionStats(), refreshPendingActions()])
+    } catch (err) {
+      updateTasks(tasks => (tasks.some(t => t.id === task.id) ? tasks : [...tasks, task]))
+      setActionError(errorMessage(err))
+    }
+  }, [updateTasks, refreshCompletionStats, refreshPendingActions])
+
+  const createTask = useCallback(async (newTask: NewTickTickTask) => {
+    const now = new Date().toISOString()
+    const placeholder: TickTickTask = {
+      id: `pending-${Date.now()}`,
+      title: newTask.title,
+      isCompleted: false,
+      priority: newTask.priority ?? 0,
+      dueDate: newTask.dueDate,
+      projectId: newTask.projectId,
+      projectName: projectName(newTask.projectId),
+      tags: [],
+      createdTime: now,
+      modifiedTime: now,
+      checklist: [],
+      // A plain YYYY-MM-DD due date creates an all-day task
+      isAllDay: !!newTask.dueDate && !newTask.dueDate.includes('T'),
+      dueDay: newTask.dueDate?.includes('T') ? undefined : newTask.dueDate,
+      reminderOffsets: [],
+    }
+
+    updateTasks(tasks => [...tasks, placeholder])
+    try {
+      const created = await invoke<TickTickTask>('create_task', { task: newTask })
+      updateTasks(tasks =>
+        tasks.map(t =>
+          t.id === placeholder.id ? { ...created, projectName: placeholder.projectName } : t
+        )
+      )
+      setActionError(null)
+      await refreshPendingActions()
+    } catch (err) {
+      updateTasks(tasks => tasks.filter(t => t.id !== placeholder.id))
+      setActionError(errorMessage(err))
+    }
+  }, [updateTasks, projectName, refreshPendingActions])
+
+  const updateTask = useCallback(async (update: TickTickTaskUpdate) => {
+    const original = data?.tasks.find(t => t.id === update.id)
+    if (!original) return
+
+    const edited: TickTickTask = {
+      ...original,
+      title: update.title ?? original.title,
+      dueDate: update.dueDate ?? original.dueDate,
+      priority: update.priority ?? original.priority,
+    }
+
+    updateTasks(tasks => tasks.map(t => (t.id === update.id ? edited : t)))
+    try {
+      const saved = await invoke<TickTickTask>('update_task', { update })
+      updateTasks(tasks =>
+        tasks.map(t => (t.id === update.id ? { ...saved, projectName: original.projectName } : t))
+      )
+      setActionError(null)
+      await refreshPendingActions()
+    } catch (err) {
+      updateTasks(tasks => tasks.map(t => (t.id === update.id ? original : t)))
+      setActionError(errorMessage(err))
+    }
+  }, [data, updateTasks, refreshPendingActions])
+
+  const discardAction = useCallback(async (id: string) => {
+    try {
+      await invoke('discard_task_action', { id })
+      setActionError(null)
+    } catch (err) {
+      setActionError(errorMessage(err))
+    }
+    // The next sync restores whatever the change had applied locally
+    await Promise.all([refreshPendingActions(), refresh(false)])
+  }, [refreshPendingActions, refresh])
+
+  const startOAuth = useCallback(async () => {
+    try {
+      setError(null)
+      // Start the OAuth flow (opens browser)
+      await invoke('start_ticktick_oauth')
+      // Wait for the callback
+      await invoke('complete_ticktick_oauth')
+      await refresh(true)
+    } catch (err) {
+      setError(errorMessage(err))
+    }
+  }, [refresh])
+
+  const queryTasks = useCallback(
+    (query: string | { list: string }, groupBy?: TaskGrouping) =>
+      invoke<TaskQueryResult>('query_tasks', {
+        ...(typeof query === 'string' ? { query } : query),
+        groupBy,
+      }),
+    []
+  )
+
   useEffect(() => {
     const setup = async () => {
       setIsLoading(true)
@@ -81,6 +269,21 @@ export function useTickTick(): UseTickTickReturn {
     }
   }, [refresh])
 
+  // Send changes queued while offline as soon as the network comes back
+  useEffect(() => {
+    const handleOnline = async () => {
+      try {
+        setPendingActions(await invoke<PendingTaskAction[]>('replay_task_actions'))
+      } catch {
+        // The next refresh retries
+      }
+      await refresh(false)
+    }
+
+    window.addEventListener('online', handleOnline)
+    return () => window.removeEventListener('online', handleOnline)
+  }, [refresh])
+
   return {
     data,
     isLoading,
@@ -88,5 +291,16 @@ export function useTickTick(): UseTickTickReturn {
     error,
     lastUpdated: data?.lastUpdated ?? null,
     refresh: () => refresh(true),
+    completionStats,
+    changedTaskIds,
+    actionError,
+    pendingActions,
+    offline: data?.offline ?? false,
+    discardAction,
+    completeTask,
+    createTask,
+    updateTask,
+    startOAuth,
+    queryTasks,
   }
 }
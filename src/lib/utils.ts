@@ -4,3 +4,15 @@ import { twMerge } from "tailwind-merge"
 export function cn(...inputs: ClassValue[]) {
   return twMerge(clsx(inputs))
 }
+
+/**
+ * When a task is due. All-day tasks use their own calendar day (local midnight),
+ * since the UTC due date can fall on the previous day in other timezones
+ */
+export function taskDueDate(task: { dueDate?: string; dueDay?: string }): Date | null {
+  if (task.dueDay) {
+    const [year, month, day] = task.dueDay.split("-").map(Number)
+    return new Date(year, month - 1, day)
+  }
+  return task.dueDate ? new Date(task.dueDate) : null
+}
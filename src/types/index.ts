@@ -22,6 +22,8 @@ export interface StockConfig {
   ticker: string
   /** Human-readable name for the stock */
   name: string
+  /** Asset type, from the watchlist or inferred from the symbol */
+  assetKind: AssetKind
 }
 
 export interface StockData {
@@ -35,16 +37,247 @@ export interface StockData {
   change: number
   /** Percentage change from previous close */
   changePercent: number
-  /** Currency symbol (e.g., "$", "C$") */
+  /** Currency symbol (e.g., "$", "C$"), empty for indices and FX rates */
   currency: string
+  /** ISO currency code (e.g., "USD", "CAD") */
+  currencyCode: string
+  /** Configured base currency, null if not set or no FX rate was available */
+  baseCurrency: string | null
+  /** Rate used to convert into the base currency */
+  fxRate: number | null
+  /** Price converted into the base currency */
+  priceInBase: number | null
+  /** Change converted into the base currency */
+  changeInBase: number | null
   /** Sparkline data points for mini chart visualization */
   sparklineData: number[]
   /** Number of decimal places for price display (from Yahoo priceHint) */
   priceHint?: number
+  /** 52-week range, when Yahoo reports it */
+  fiftyTwoWeekHigh: number | null
+  fiftyTwoWeekLow: number | null
+  /** Dividends and splits over the last year */
+  corporateActions: CorporateActions
+  /** Trading session of the stock's exchange */
+  market: MarketSession
+  /** Latest pre/post-market trade, null during regular hours or when closed */
+  extendedHours: ExtendedHoursQuote | null
   /** Timestamp of last update */
   lastUpdated: Date
 }
 
+export type AlertCondition =
+  | { kind: 'above'; price: number }
+  | { kind: 'below'; price: number }
+  | { kind: 'percent_change'; percent: number }
+  | { kind: 'fifty_two_week_high' }
+  | { kind: 'fifty_two_week_low' }
+
+/** Payload of the `stock-alert` event */
+export interface StockAlert {
+  ticker: string
+  name: string
+  condition: AlertCondition
+  price: number
+  currency: string
+  /** Human-readable summary, e.g. "ZGLD.TO is up 3.20% today, at C$28.10" */
+  message: string
+  triggeredAt: string
+}
+
+export interface DividendEvent {
+  /** Ex-dividend date (YYYY-MM-DD) */
+  date: string
+  /** Amount per share in the stock's currency */
+  amount: number
+}
+
+export interface SplitEvent {
+  /** Effective date (YYYY-MM-DD) */
+  date: string
+  numerator: number
+  denominator: number
+}
+
+export interface CorporateActions {
+  dividends: DividendEvent[]
+  splits: SplitEvent[]
+  /** Sum of the last 12 months of dividends, per share */
+  trailingAnnualDividend: number | null
+  /** Trailing dividend as a percentage of the current price */
+  dividendYield: number | null
+  /** Projected from the payment cadence, not an announced date */
+  estimatedNextDividend: string | null
+}
+
+export type AssetKind = 'equity' | 'crypto' | 'fx' | 'index' | 'commodity'
+
+/** `unknown` when the market data provider doesn't report trading hours */
+export type SessionState = 'pre' | 'regular' | 'post' | 'closed' | 'unknown'
+
+export interface MarketSession {
+  state: SessionState
+  /** IANA timezone of the exchange */
+  exchangeTimezone: string | null
+  /** Time of the last regular-hours trade (ISO 8601) */
+  regularMarketTime: string | null
+  /** Estimated start of the next regular session (ISO 8601) */
+  nextOpen: string | null
+}
+
+export interface ExtendedHoursQuote {
+  session: SessionState
+  price: number
+  /** Change from the regular-hours price */
+  change: number
+  changePercent: number
+}
+
+export type ChartRange = '1d' | '5d' | '1mo' | '6mo' | '1y' | '5y'
+
+export interface OhlcPoint {
+  /** Unix timestamp in seconds */
+  timestamp: number
+  /** Opening price for the bar */
+  open: number
+  /** Highest price for the bar */
+  high: number
+  /** Lowest price for the bar */
+  low: number
+  /** Closing price for the bar */
+  close: number
+  /** Traded volume, if reported */
+  volume: number | null
+}
+
+export interface StockChart {
+  /** Stock ticker symbol */
+  ticker: string
+  /** Requested chart range */
+  range: ChartRange
+  /** Bar size (e.g., "5m", "1d") */
+  interval: string
+  /** ISO currency code (e.g., "USD", "CAD") */
+  currencyCode: string
+  /** Number of decimal places for price display */
+  priceHint: number
+  /** Close before the first point, for drawing a baseline */
+  previousClose: number | null
+  /** Price bars, oldest first */
+  points: OhlcPoint[]
+  /** Timestamp of last update */
+  lastUpdated: string
+}
+
+/** `not-found` means the provider doesn't know the symbol, usually a typo */
+export type FetchStatus = 'ok' | 'timeout' | 'not-found' | 'error'
+
+export interface TickerStatus {
+  /** Stock ticker symbol */
+  ticker: string
+  /** Outcome of the last fetch for this ticker */
+  status: FetchStatus
+  /** Error message when the fetch failed */
+  error?: string
+}
+
+/** Result of `validate_tickers` for one symbol */
+export interface TickerValidation {
+  ticker: string
+  status: FetchStatus
+  name: string | null
+  assetKind: AssetKind | null
+  currencyCode: string | null
+  error: string | null
+}
+
+/** Result of `search_symbols` */
+export interface SymbolMatch {
+  symbol: string
+  name: string
+  /** Exchange display name, e.g. "Toronto" */
+  exchange: string
+  assetKind: AssetKind
+  /** Provider's type label, e.g. "ETF" */
+  typeDisplay: string
+  currencyCode: string | null
+}
+
+export interface StocksData {
+  /** Quotes for tickers that could be fetched */
+  stocks: StockData[]
+  /** Fetch status for every configured ticker */
+  statuses: TickerStatus[]
+  /** Suggested delay before the next refresh */
+  refreshIntervalMinutes: number
+  /** Timestamp of last update */
+  lastUpdated: Date
+}
+
+export interface PortfolioHolding {
+  /** Stock ticker symbol */
+  ticker: string
+  /** Human-readable name for the stock */
+  name: string
+  /** Currency symbol (e.g., "$", "C$"), empty for indices and FX rates */
+  currency: string
+  /** ISO currency code (e.g., "USD", "CAD") */
+  currencyCode: string
+  /** Total shares across all lots */
+  shares: number
+  /** Current price per share */
+  price: number
+  /** Weighted average price paid per share */
+  averageCost: number
+  /** Total amount paid across all lots */
+  costBasis: number
+  /** Current value of the position */
+  marketValue: number
+  /** Change in position value since the previous close */
+  dayChange: number
+  /** Market value minus cost basis */
+  unrealizedGain: number
+  /** Unrealized gain as a percentage of cost basis */
+  unrealizedGainPercent: number
+  /** Rate used to convert into the base currency (1 without one) */
+  fxRate: number
+  /** Market value in the base currency */
+  marketValueBase: number
+  /** Day change in the base currency */
+  dayChangeBase: number
+  /** Unrealized gain in the base currency */
+  unrealizedGainBase: number
+  /** Projected dividends over the next year at the trailing rate, null for non-payers */
+  annualIncome: number | null
+  /** Projected annual income in the base currency */
+  annualIncomeBase: number | null
+  /** Annual income as a percentage of cost basis */
+  yieldOnCost: number | null
+  /** Share of total portfolio value (0-100) */
+  weight: number
+}
+
+export interface Portfolio {
+  /** Individual positions */
+  holdings: PortfolioHolding[]
+  /** Currency the totals are in, null if totals mix currencies */
+  baseCurrency: string | null
+  /** Sum of all position values */
+  totalMarketValue: number
+  /** Sum of all cost bases */
+  totalCostBasis: number
+  /** Sum of all day changes */
+  totalDayChange: number
+  /** Total market value minus total cost basis */
+  totalUnrealizedGain: number
+  /** Total unrealized gain as a percentage of total cost basis */
+  totalUnrealizedGainPercent: number
+  /** Projected dividends over the next year across all holdings */
+  totalAnnualIncome: number
+  /** Timestamp of last update */
+  lastUpdated: string
+}
+
 // ============================================================================
 // Weather Types
 // ============================================================================
@@ -58,16 +291,32 @@ export interface HourlyWeather {
   condition: WeatherCondition
 }
 
-export type WeatherCondition =
-  | 'sunny'
+export type ConditionCategory =
+  | 'clear'
   | 'partly-cloudy'
   | 'cloudy'
-  | 'overcast'
+  | 'fog'
+  | 'drizzle'
   | 'rain'
   | 'snow'
   | 'thunderstorm'
-  | 'fog'
-  | 'clear'
+
+export type Intensity = 'light' | 'moderate' | 'heavy'
+
+export type PrecipitationType = 'rain' | 'freezing' | 'snow' | 'hail'
+
+export interface WeatherCondition {
+  /** Broad condition category */
+  category: ConditionCategory
+  /** Precipitation intensity (null for dry conditions) */
+  intensity: Intensity | null
+  /** Precipitation type (null for dry conditions) */
+  precipitation: PrecipitationType | null
+  /** Whether the sun is up, for day/night icon variants */
+  isDay: boolean
+  /** Human readable summary (e.g., "Heavy freezing rain") */
+  description: string
+}
 
 export interface WeatherLocation {
   /** Location name (e.g., "Toronto") */
@@ -78,6 +327,57 @@ export interface WeatherLocation {
   longitude: number
 }
 
+export type AqiCategory =
+  | 'good'
+  | 'moderate'
+  | 'unhealthy-sensitive'
+  | 'unhealthy'
+  | 'very-unhealthy'
+  | 'hazardous'
+  | 'unknown'
+
+export interface PollenCounts {
+  /** Pollen counts in grains/m³ (Europe only, null outside pollen season) */
+  alder: number | null
+  birch: number | null
+  grass: number | null
+  mugwort: number | null
+  olive: number | null
+  ragweed: number | null
+}
+
+export interface AirQuality {
+  /** US AQI (0-500) */
+  aqi: number | null
+  /** EPA category for the AQI value */
+  aqiCategory: AqiCategory
+  /** Fine particulate matter in μg/m³ */
+  pm25: number | null
+  /** Coarse particulate matter in μg/m³ */
+  pm10: number | null
+  /** Ozone in μg/m³ */
+  ozone: number | null
+  /** Pollen counts by plant */
+  pollen: PollenCounts
+  /** Timestamp of last update */
+  lastUpdated: string
+}
+
+export interface SensorReading {
+  /** Sensor display name */
+  name: string
+  /** Where the sensor is placed */
+  location: 'indoor' | 'outdoor'
+  /** Temperature in celsius */
+  temperature: number | null
+  /** Relative humidity percentage (0-100) */
+  humidity: number | null
+  /** CO2 concentration in ppm */
+  co2: number | null
+  /** Timestamp of last update */
+  lastUpdated: string
+}
+
 export interface WeatherData {
   /** Location information */
   location: WeatherLocation
@@ -97,12 +397,55 @@ export interface WeatherData {
   sunrise: string
   /** Sunset time as ISO string or Date */
   sunset: string
-  /** Hourly forecast data (24 hours) */
+  /** Hourly forecast for today, possibly without hours that have passed */
   hourlyForecast: HourlyWeather[]
+  /** Current air quality, null if the air quality API was unavailable */
+  airQuality: AirQuality | null
+  /** Readings from local sensors, measured rather than forecast */
+  measured: SensorReading[]
   /** Timestamp of last update */
   lastUpdated: Date
 }
 
+export interface DailyWeatherSummary {
+  /** Local date in "YYYY-MM-DD" format */
+  date: string
+  /** Daily high temperature */
+  high: number
+  /** Daily low temperature */
+  low: number
+  /** Total precipitation in mm */
+  precipitation: number
+  /** Dominant condition for the day */
+  condition: WeatherCondition
+  /** Hourly observations (24 hours) */
+  hourly: HourlyWeather[]
+}
+
+export interface PeriodSummary {
+  /** First date in the period ("YYYY-MM-DD") */
+  startDate: string
+  /** Last date in the period ("YYYY-MM-DD") */
+  endDate: string
+  /** Average daily high, null if no days were recorded */
+  avgHigh: number | null
+  /** Average daily low, null if no days were recorded */
+  avgLow: number | null
+  /** Total precipitation in mm */
+  totalPrecipitation: number
+  /** Days with at least 1mm of precipitation */
+  wetDays: number
+}
+
+export interface WeatherHistory {
+  /** Daily summaries, oldest first, ending with today */
+  days: DailyWeatherSummary[]
+  /** The last 7 days including today */
+  thisWeek: PeriodSummary
+  /** The 7 days before that */
+  lastWeek: PeriodSummary
+}
+
 // ============================================================================
 // Dashboard Types
 // ============================================================================
@@ -146,6 +489,68 @@ export interface TickTickTask {
   createdTime: string
   /** Modified date (ISO string) */
   modifiedTime: string
+  /** Task description */
+  notes?: string
+  /** Checklist (subtask) items */
+  checklist: ChecklistItem[]
+  /** Completed checklist items, absent without a checklist */
+  checklistProgress?: { completed: number; total: number }
+  /** RRULE for repeating tasks */
+  recurrence?: string
+  /** Whether the due date is a whole day rather than a time */
+  isAllDay: boolean
+  /** IANA timezone the dates were set in */
+  timeZone?: string
+  /** Due day of an all-day task in its own timezone (YYYY-MM-DD) */
+  dueDay?: string
+  /** Reminder offsets in minutes relative to the due date (negative = before) */
+  reminderOffsets: number[]
+  /** When the task was completed (ISO string) */
+  completedTime?: string
+}
+
+export type CompletedRange = 'today' | 'week' | 'month'
+
+export interface CompletionStats {
+  today: number
+  /** Completions since Monday */
+  thisWeek: number
+  /** Consecutive days with a completion, ending today or yesterday */
+  streakDays: number
+  /** Completions per day across the requested range */
+  daily: { date: string; count: number }[]
+}
+
+export interface CompletedTasks {
+  range: CompletedRange
+  /** Most recently completed first */
+  tasks: TickTickTask[]
+  stats: CompletionStats
+}
+
+export interface ChecklistItem {
+  id: string
+  title: string
+  isCompleted: boolean
+  sortOrder: number
+}
+
+export interface NewTickTickTask {
+  title: string
+  projectId: string
+  /** ISO datetime, or YYYY-MM-DD for an all-day task */
+  dueDate?: string
+  /** 0=none, 1=low, 3=medium, 5=high */
+  priority?: number
+}
+
+export interface TickTickTaskUpdate {
+  id: string
+  projectId: string
+  /** Fields left undefined keep their current value */
+  title?: string
+  dueDate?: string
+  priority?: number
 }
 
 export interface TickTickProject {
@@ -164,10 +569,104 @@ export interface TickTickData {
   tasks: TickTickTask[]
   /** List of projects/lists */
   projects: TickTickProject[]
+  /** Projects whose tasks couldn't be fetched this refresh */
+  failedProjects: ProjectFailure[]
+  /** Changes since the previous sync (empty when served from the local store) */
+  changes: TaskChanges
+  /** Changes made here that haven't reached TickTick yet, or that it rejected */
+  pendingActions: PendingTaskAction[]
+  /** TickTick couldn't be reached; tasks are from the last sync plus pending changes */
+  offline: boolean
   /** Timestamp of last update */
   lastUpdated: Date
 }
 
+export type TaskGrouping = 'none' | 'project' | 'due' | 'priority'
+
+export interface TaskGroup {
+  key: string
+  label: string
+  tasks: TickTickTask[]
+}
+
+export interface TaskQueryResult {
+  query: string
+  groupBy: TaskGrouping
+  /** Number of matching tasks across all groups */
+  total: number
+  groups: TaskGroup[]
+  lastUpdated: string
+}
+
+export interface TaskChanges {
+  added: TickTickTask[]
+  updated: TickTickTask[]
+  /** IDs of tasks that were completed, deleted or moved */
+  removed: string[]
+}
+
+export interface ProjectFailure {
+  projectId: string
+  projectName: string
+  error: string
+}
+
+export type TaskAction =
+  | { kind: 'complete'; projectId: string; taskId: string }
+  /** localId is the placeholder ID the task has until TickTick creates it */
+  | { kind: 'create'; task: NewTickTickTask; localId: string }
+  | { kind: 'update'; update: TickTickTaskUpdate }
+
+/** A task change made offline, replayed in order once TickTick is reachable */
+export interface PendingTaskAction {
+  id: string
+  action: TaskAction
+  /** When the change was made (ISO string) */
+  queuedAt: string
+  /** The task's modifiedTime when the change was made */
+  baseModifiedTime?: string
+  attempts: number
+  /** Why TickTick rejected the change or it lost a conflict; failed changes wait to be discarded */
+  error?: string
+}
+
+// ============================================================================
+// Task Provider Types
+// ============================================================================
+
+export type TaskProviderKind = 'ticktick' | 'caldav' | 'todoist' | 'file'
+
+/** An open task from any provider */
+export interface Task {
+  id: string
+  listId: string
+  listName?: string
+  title: string
+  notes?: string
+  /** ISO datetime, or YYYY-MM-DD when isAllDay */
+  dueDate?: string
+  isAllDay: boolean
+  /** 0=none, 1=low, 3=medium, 5=high */
+  priority: number
+  tags: string[]
+  isCompleted: boolean
+  /** Last modified (ISO string), when the provider tracks it */
+  modifiedTime?: string
+}
+
+export interface TaskList {
+  id: string
+  name: string
+  color?: string
+}
+
+export interface TasksData {
+  provider: TaskProviderKind
+  tasks: Task[]
+  lists: TaskList[]
+  lastUpdated: string
+}
+
 // ============================================================================
 // Calendar Types
 // ============================================================================
@@ -213,6 +712,44 @@ export interface CalendarSource {
   color: string
 }
 
+// ============================================================================
+// Agenda Types
+// ============================================================================
+
+export type AgendaItemKind = 'event' | 'task'
+
+export interface AgendaItem {
+  kind: AgendaItemKind
+  /** Event or task ID */
+  id: string
+  title: string
+  /** Start time (ISO string, local timezone) */
+  start: string
+  /** Exclusive end time; whole-day items end at the following midnight */
+  end: string
+  allDay: boolean
+  /** Timezone the event or task was created in */
+  timeZone?: string
+  /** Calendar color for events */
+  color?: string
+  /** Calendar or project name */
+  source: string
+  event?: CalendarEvent
+  task?: Task
+}
+
+export interface Agenda {
+  /** Items sorted by day, whole-day items first */
+  items: AgendaItem[]
+  /** Range start (ISO string) */
+  start: string
+  /** Range end (ISO string) */
+  end: string
+  /** Sources that failed to load */
+  errors: string[]
+  lastUpdated: string
+}
+
 // ============================================================================
 // Timeline Types
 // ============================================================================